@@ -3,10 +3,13 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use roulette_game::msg::{
-    AllStateResponse, BetConfig, BetsInfoResponse, ConfigResponse, Direction, ExecuteMsg,
-    InstantiateMsg, PointRatioInfo, QueryMsg, RoomInfoResponse, RoomsInfoResponse, StateResponse,
+    AllStateResponse, BankrollResponse, BetConfig, BetsInfoResponse, CallBetKind,
+    CloseableRoundsResponse, ConfigResponse, Direction, ExecuteMsg, InstantiateMsg,
+    LiquidityResponse, MaxAllowedBetResponse, PendingWinningsResponse, PointRatioInfo, QueryMsg,
+    ReceiveMsg, RoomInfoResponse, RoomOddsResponse, RoomsInfoResponse, RoundExposureResponse,
+    RoundProofResponse, SimulateBetResponse, StateResponse, WithdrawalQueueResponse,
 };
-use roulette_game::state::{AssetInfo, BetInfo, Config, RoomConfig, State};
+use roulette_game::state::{AssetInfo, BetInfo, Config, PayoutTable, RoomConfig, State};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -21,6 +24,7 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
     export_schema(&schema_for!(AssetInfo), &out_dir);
     export_schema(&schema_for!(BetInfo), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
@@ -30,9 +34,21 @@ fn main() {
     export_schema(&schema_for!(BetsInfoResponse), &out_dir);
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(Direction), &out_dir);
+    export_schema(&schema_for!(CallBetKind), &out_dir);
     export_schema(&schema_for!(PointRatioInfo), &out_dir);
     export_schema(&schema_for!(RoomInfoResponse), &out_dir);
     export_schema(&schema_for!(RoomsInfoResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
     export_schema(&schema_for!(AllStateResponse), &out_dir);
+    export_schema(&schema_for!(RoundProofResponse), &out_dir);
+    export_schema(&schema_for!(PendingWinningsResponse), &out_dir);
+    export_schema(&schema_for!(BankrollResponse), &out_dir);
+    export_schema(&schema_for!(RoomOddsResponse), &out_dir);
+    export_schema(&schema_for!(CloseableRoundsResponse), &out_dir);
+    export_schema(&schema_for!(LiquidityResponse), &out_dir);
+    export_schema(&schema_for!(MaxAllowedBetResponse), &out_dir);
+    export_schema(&schema_for!(RoundExposureResponse), &out_dir);
+    export_schema(&schema_for!(SimulateBetResponse), &out_dir);
+    export_schema(&schema_for!(WithdrawalQueueResponse), &out_dir);
+    export_schema(&schema_for!(PayoutTable), &out_dir);
 }
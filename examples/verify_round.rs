@@ -0,0 +1,64 @@
+//! Recomputes the winning pocket of a round from its `RoundProof` query response.
+//!
+//! cargo run --example verify_round -- round_proof.json [drand_pubkey_hex]
+use std::env::args;
+use std::fs::read;
+
+use cosmwasm_std::{from_slice, HexBinary};
+use roulette_game::drand::{beacon_randomness, verify_beacon};
+use roulette_game::msg::RoundProofResponse;
+use roulette_game::rand::{rand_generator, sha_256};
+
+fn main() {
+    let args: Vec<String> = args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: verify_round <round_proof.json> [drand_pubkey_hex]");
+        std::process::exit(2);
+    }
+
+    let response: RoundProofResponse = from_slice(&read(&args[1]).unwrap()).unwrap();
+    let proof = response.proof;
+    let mut fair = true;
+
+    if let Some(commitment) = &proof.commitment {
+        let matches = sha_256(&proof.seed) == commitment.as_slice();
+        println!("revealed secret matches the commitment: {}", matches);
+        fair &= matches;
+    }
+
+    if let Some(beacon) = &proof.beacon {
+        let matches = beacon_randomness(&beacon.signature) == proof.seed.as_slice();
+        println!(
+            "seed is the randomness of drand round {}: {}",
+            beacon.round, matches
+        );
+        fair &= matches;
+
+        if let Some(pubkey) = args.get(2) {
+            let pubkey = HexBinary::from_hex(pubkey).unwrap();
+            let valid = verify_beacon(&pubkey, beacon.round, &beacon.signature);
+            println!("beacon signature is valid: {}", valid);
+            fair &= valid;
+        }
+    }
+
+    if let Some(job_id) = &proof.job_id {
+        println!(
+            "seed was delivered by the randomness proxy for job {}",
+            job_id
+        );
+    }
+
+    let pocket = rand_generator(&proof.seed, &proof.entropy, proof.pockets);
+    println!(
+        "room {} round {}: recomputed pocket {}, recorded pocket {}",
+        response.room_id, response.round_id, pocket, proof.winner
+    );
+    fair &= pocket == proof.winner;
+
+    if !fair {
+        println!("the round proof does NOT verify");
+        std::process::exit(1);
+    }
+    println!("the round proof verifies");
+}
@@ -0,0 +1,94 @@
+use std::convert::TryFrom;
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{pairing, G1Affine, G2Affine, G2Projective};
+use sha2::Sha256;
+
+use crate::rand::sha_256;
+
+/// Domain separation tag of the drand `pedersen-bls-unchained` scheme (signatures on G2).
+const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Returns the first drand round that is published strictly after `time`.
+/// Round 1 is published at `genesis_time` and a new round follows every `period` seconds.
+pub fn drand_round_after(time: u64, genesis_time: u64, period: u64) -> u64 {
+    if time < genesis_time {
+        return 1;
+    }
+    (time - genesis_time) / period + 2
+}
+
+/// Returns the time drand `round` is published at.
+pub fn drand_round_time(round: u64, genesis_time: u64, period: u64) -> u64 {
+    genesis_time + round.saturating_sub(1) * period
+}
+
+/// Checks an unchained drand beacon: the signature must be a valid BLS signature of
+/// sha256(round) under the group public key.
+pub fn verify_beacon(pubkey: &[u8], round: u64, signature: &[u8]) -> bool {
+    let pubkey = match <[u8; 48]>::try_from(pubkey) {
+        Ok(bytes) => G1Affine::from_compressed(&bytes),
+        Err(_) => return false,
+    };
+    let signature = match <[u8; 96]>::try_from(signature) {
+        Ok(bytes) => G2Affine::from_compressed(&bytes),
+        Err(_) => return false,
+    };
+    if pubkey.is_none().into() || signature.is_none().into() {
+        return false;
+    }
+
+    let message = sha_256(&round.to_be_bytes());
+    let hashed_message = G2Affine::from(
+        <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message, DST),
+    );
+
+    pairing(&G1Affine::generator(), &signature.unwrap())
+        == pairing(&pubkey.unwrap(), &hashed_message)
+}
+
+/// The drand randomness of a beacon is the sha256 hash of its signature.
+pub fn beacon_randomness(signature: &[u8]) -> [u8; 32] {
+    sha_256(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::HexBinary;
+
+    /// Beacons of a test drand group, signed offline with a throwaway key.
+    const PUBKEY: &str = "adf63187b71c4c0db9833ea07a84bec8597c5148617143e72b1a8a5f0f64d5b0119e018df861ed8df7af185c17e60103";
+    const ROUND_6_SIGNATURE: &str = "8cd9e58df277c6435a70dab3813519a06d160a9b1c08cee31ba42b6927f6d33ea91da496e7147347d8b5ec25358eeab70a616a1fd2cbe064e843789f491bbf1ba05d587d0d77f39a0472f23875a00fb9b5c93405659b09b06f415b249c3fc43d";
+    const ROUND_7_SIGNATURE: &str = "8745ef7aa940b0e5542c22192d1c1116538b2d7a1f52c17e26e2031ec3bde74316d0085165c39be8c4de4ccea93b36ef096ddb6444beea5973a1a3294946920cbbc319c7a7fd64549237b4823b58cd3d5ac071b16e19affa7fc7633de80d8650";
+
+    #[test]
+    fn test_verify_beacon() {
+        let pubkey = HexBinary::from_hex(PUBKEY).unwrap();
+        let round_6 = HexBinary::from_hex(ROUND_6_SIGNATURE).unwrap();
+        let round_7 = HexBinary::from_hex(ROUND_7_SIGNATURE).unwrap();
+
+        assert!(verify_beacon(&pubkey, 6, &round_6));
+        assert!(verify_beacon(&pubkey, 7, &round_7));
+        // signature of another round
+        assert!(!verify_beacon(&pubkey, 7, &round_6));
+        // malformed inputs
+        assert!(!verify_beacon(&pubkey, 6, &round_6[1..]));
+        assert!(!verify_beacon(&round_6, 6, &round_6));
+    }
+
+    #[test]
+    fn test_drand_round_after() {
+        assert_eq!(drand_round_after(0, 100, 30), 1);
+        assert_eq!(drand_round_after(100, 100, 30), 2);
+        assert_eq!(drand_round_after(129, 100, 30), 2);
+        assert_eq!(drand_round_after(130, 100, 30), 3);
+    }
+
+    #[test]
+    fn test_drand_round_time() {
+        assert_eq!(drand_round_time(1, 100, 30), 100);
+        assert_eq!(drand_round_time(3, 100, 30), 160);
+        assert_eq!(drand_round_after(drand_round_time(3, 100, 30), 100, 30), 4);
+    }
+}
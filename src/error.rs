@@ -19,6 +19,17 @@ pub enum ContractError {
     #[error("Incorrect native denom: provided: {provided}, required: {required}")]
     IncorrectNativeDenom { provided: String, required: String },
 
+    #[error("Incorrect cw20 token: provided: {provided}, required: {required}")]
+    IncorrectCw20Token { provided: String, required: String },
+
+    #[error("The tokens of a cw20 room must be sent with the cw20 Send message")]
+    Cw20SendRequired {},
+
+    #[error(
+        "Only the denom of the room can be sent, the other funds would not belong to any room"
+    )]
+    UnexpectedFunds {},
+
     #[error("This round is not started, so you can not close it")]
     RoundNotStarted {},
 
@@ -28,6 +39,102 @@ pub enum ContractError {
     #[error("This round is finished, you can not bet")]
     RoundFinished {},
 
+    #[error("No more bets, this round is about to close")]
+    NoMoreBets {},
+
+    #[error("The no more bets window must be shorter than the round")]
+    InvalidBettingWindow {},
+
+    #[error("The operator has not committed a secret for this round yet")]
+    RoundNotCommitted {},
+
+    #[error("A secret is already committed for this round")]
+    RoundAlreadyCommitted {},
+
+    #[error("The commitment must be a 32 bytes sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("The revealed secret does not match the commitment of this round")]
+    InvalidReveal {},
+
+    #[error("The operator can still reveal the secret, so you can not refund this round")]
+    RevealPending {},
+
+    #[error("The round can still be closed, so you can not refund it yet")]
+    RefundPending {},
+
+    #[error("Round {round_id} of this room is already closed")]
+    RoundClosed { round_id: u64 },
+
+    #[error("This action is not supported by the configured randomness source")]
+    WrongRandomnessSource {},
+
+    #[error("This round is bound to drand round {expected}, provided: {provided}")]
+    WrongDrandRound { expected: u64, provided: u64 },
+
+    #[error("Drand round {drand_round} of this round is published, close it with its beacon")]
+    BeaconPublished { drand_round: u64 },
+
+    #[error("The drand period must be positive and the drand public key 48 bytes")]
+    InvalidDrandConfig {},
+
+    #[error("The refund timeout can not be shorter than the reveal timeout")]
+    InvalidTimeouts {},
+
+    #[error("The drand beacon signature is invalid")]
+    InvalidBeacon {},
+
+    #[error("The randomness of this round is requested and not delivered yet")]
+    RoundDrawing {},
+
+    #[error("No round is waiting for the randomness job {job_id}")]
+    UnknownRandomnessJob { job_id: String },
+
+    #[error("You have no bet in the living round of this room")]
+    NoBet {},
+
+    #[error("The bets of this round are locked, you can not cancel or modify them")]
+    BetsLocked {},
+
+    #[error("The last round of this room is still being settled, you can not bet")]
+    RoundSettling {},
+
+    #[error("Round {round_id} of this room has no pending settlement")]
+    NoPendingSettlement { round_id: u64 },
+
+    #[error(
+        "The {bet_type} payout must return at least the stake and keep the minimum house edge"
+    )]
+    InvalidPayout { bet_type: String },
+
+    #[error("The keeper reward must be a share of the platform fee, at most 1")]
+    InvalidKeeperReward {},
+
+    #[error("The risk fraction must be above 0 and at most 1")]
+    InvalidRiskFraction {},
+
+    #[error("These bets would pay {payout} on pocket {pocket}, the room risks at most {max_payout} on one pocket now")]
+    RiskLimitExceeded {
+        pocket: u32,
+        payout: Uint128,
+        max_payout: Uint128,
+    },
+
+    #[error("The payouts can not change while the living round has bets")]
+    BetsPlaced {},
+
+    #[error("You only have {shares} LP shares in this room")]
+    InsufficientShares { shares: Uint128 },
+
+    #[error("There is no queued withdrawal {withdrawal_id} in this room")]
+    UnknownWithdrawal { withdrawal_id: u64 },
+
+    #[error("Liquidity can not move from the betting close until the round is settled")]
+    PoolLocked {},
+
+    #[error("You have nothing to claim")]
+    NothingToClaim {},
+
     #[error("You can withdraw ax maximum {withdrawal_amount} because of current user's betting reward for maximum case, now you are trying to withdraw {amount}")]
     WithdrawalMoneyExceeded {
         withdrawal_amount: Uint128,
@@ -1,29 +1,40 @@
+use crate::drand::{beacon_randomness, drand_round_after, drand_round_time, verify_beacon};
 use crate::error::ContractError;
 use crate::msg::{
-    BetConfig, BetsInfoResponse, Direction, ExecuteMsg, InstantiateMsg, MigrateMsg, PointRatioInfo,
+    BetConfig, BetsInfoResponse, CallBetKind, Direction, DrandBeacon, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, PointRatioInfo, RandomnessCallback, RandomnessProxyExecuteMsg, ReceiveMsg,
 };
 use crate::state::{
-    bet_info_key, bet_info_storage, AssetInfo, BetInfo, Config, RoomConfig, State, CONFIG,
-    MINIMUMRESERVE, ROOMS, ROUND_START_SECOND, STATE, WINNERNUMBER,
+    bet_info_key, bet_info_storage, lp_shares_key, round_key, AssetInfo, Bankroll, BetInfo, Config,
+    Exposure, PayoutTable, RandomnessSource, RoomConfig, RoundProof, Settlement, State, Wheel,
+    WithdrawalRequest, ZeroRule, BANKROLL, CLAIMABLE, CONFIG, LIVING_ROUND, LP_SHARES,
+    NEXT_WITHDRAWAL_ID, ROOMS, ROUND_COMMITMENT, ROUND_DRAND, ROUND_DRAWING, ROUND_EXPOSURE,
+    ROUND_KEEPER, ROUND_PROOF, ROUND_START_SECOND, SETTLEMENT, STATE, VOID_ROUNDS, WINNERNUMBER,
+    WITHDRAWAL_QUEUE,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_binary, Addr, Attribute, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
-    Event, MessageInfo, Order, QueryRequest, Response, StdError, StdResult, Uint128, WasmMsg,
-    WasmQuery,
+    attr, from_binary, to_binary, Addr, Attribute, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, QueryRequest, Response, StdError, StdResult, Storage, Uint128,
+    Uint256, WasmMsg, WasmQuery,
 };
-use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw20::{Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw721::{AllNftInfoResponse, Cw721QueryMsg};
+use cw_storage_plus::Bound;
+use std::convert::TryFrom;
 
-use crate::rand::{sha_256, Prng};
-
-use rand::{RngCore, SeedableRng};
-use rand_chacha::ChaChaRng;
+use crate::rand::{rand_generator, sha_256};
 
 const CONTRACT_NAME: &str = "Cosmos-first-roulette-gaming";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const MAXIMUM_SELECT: usize = 19;
+/// the double zero pocket of the american wheel
+const DOUBLE_ZERO: u32 = 37;
+const DEFAULT_SETTLE_LIMIT: u32 = 30;
+const MAX_SETTLE_LIMIT: u32 = 100;
+/// queued withdrawals paid at most by one settlement
+const MAX_WITHDRAWALS_PER_SETTLEMENT: usize = 10;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -34,16 +45,15 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     /* Validate addresses */
+    validate_config(&msg.config)?;
     CONFIG.save(deps.storage, &msg.config)?;
     STATE.save(
         deps.storage,
         &(State {
-            living_round: 0,
             is_haulted: false,
             room_id: 0,
         }),
     )?;
-    MINIMUMRESERVE.save(deps.storage, &Uint128::new(0))?;
     Ok(Response::new().add_attribute("action", "init_contract"))
 }
 
@@ -70,24 +80,77 @@ pub fn execute(
     match msg {
         ExecuteMsg::UpdateConfig { config } => execute_update_config(deps, info, config),
         ExecuteMsg::AddRoom { room_info } => execute_add_room(deps, info, room_info),
-        ExecuteMsg::Bet { room_id, bet_info } => execute_bet(deps, env, info, room_id, bet_info),
-        ExecuteMsg::CloseRound {} => execute_close(deps, env, info),
+        ExecuteMsg::Bet {
+            room_id,
+            bet_info,
+            replace,
+        } => execute_bet(
+            deps,
+            env,
+            info,
+            None,
+            room_id,
+            bet_info,
+            BetChange::from_replace(replace),
+        ),
+        ExecuteMsg::ModifyBet { room_id, bet_info } => {
+            execute_bet(deps, env, info, None, room_id, bet_info, BetChange::Modify)
+        }
+        ExecuteMsg::CancelBet { room_id } => {
+            execute_bet(deps, env, info, None, room_id, vec![], BetChange::Cancel)
+        }
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::CommitRound {
+            room_id,
+            commitment,
+        } => execute_commit_round(deps, info, room_id, commitment),
+        ExecuteMsg::CloseRound {
+            room_id,
+            secret,
+            beacon,
+        } => execute_close(deps, env, info, room_id, secret, beacon),
+        ExecuteMsg::ReceiveRandomness { callback } => {
+            execute_receive_randomness(deps, info, callback)
+        }
+        ExecuteMsg::RefundRound { room_id, round_id } => {
+            execute_refund_round(deps, env, info, room_id, round_id)
+        }
+        ExecuteMsg::SettleRound {
+            room_id,
+            round_id,
+            limit,
+        } => execute_settle_round(deps, env, room_id, round_id, limit),
+        ExecuteMsg::CollectFees { room_id } => execute_collect_fees(deps, info, room_id),
+        ExecuteMsg::Claim { room_id } => execute_claim(deps, info, room_id),
+        ExecuteMsg::ClaimAll {} => execute_claim_all(deps, info),
         ExecuteMsg::WithdrawFromPool { room_id, amount } => {
             execute_withdraw_from_pool(deps, env, info, room_id, amount)
         }
+        ExecuteMsg::QueueWithdrawal { room_id, shares } => {
+            execute_queue_withdrawal(deps, env, info, room_id, shares)
+        }
+        ExecuteMsg::CancelWithdrawal {
+            room_id,
+            withdrawal_id,
+        } => execute_cancel_withdrawal(deps, info, room_id, withdrawal_id),
         ExecuteMsg::Deposit { room_id, amount } => {
-            execute_deposit(deps, env, info, room_id, amount)
+            execute_deposit(deps, env, info, None, room_id, amount)
         }
         ExecuteMsg::ChangeRoomConfig {
             room_id,
             room_name,
             nft_id,
         } => execute_change_room_config(deps, info, room_id, room_name, nft_id),
+        ExecuteMsg::UpdatePayoutTable {
+            room_id,
+            payout_table,
+        } => execute_update_payout_table(deps, info, room_id, payout_table),
         ExecuteMsg::UpdateBetLimit {
             room_id,
             max_bet,
             min_bet,
-        } => execute_update_bet_limit(deps, info, room_id, max_bet, min_bet),
+            risk_fraction,
+        } => execute_update_bet_limit(deps, info, room_id, max_bet, min_bet, risk_fraction),
     }
 }
 
@@ -97,6 +160,7 @@ fn execute_update_config(
     config: Config,
 ) -> Result<Response, ContractError> {
     assert_is_admin(deps.as_ref(), info)?;
+    validate_config(&config)?;
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -113,7 +177,7 @@ fn execute_add_room(
     let config = CONFIG.load(deps.storage)?;
     let state = STATE.load(deps.storage)?;
     STATE.update(deps.storage, |mut state| -> StdResult<_> {
-        state.room_id = state.room_id + 1;
+        state.room_id += 1;
         Ok(state)
     })?;
 
@@ -129,58 +193,319 @@ fn execute_add_room(
     //increase the room id by one
     let new_room_id = state.room_id + 1;
 
+    validate_payout_table(&config, &room_info)?;
+    if room_info.no_more_bets_seconds >= room_info.next_round_seconds {
+        return Err(ContractError::InvalidBettingWindow {});
+    }
+    if room_info.keeper_reward > Some(Decimal::one()) {
+        return Err(ContractError::InvalidKeeperReward {});
+    }
+    validate_risk_fraction(room_info.risk_fraction)?;
+
     //add new room.
     ROOMS.save(deps.storage, &new_room_id.to_string(), &room_info)?;
+    LIVING_ROUND.save(deps.storage, &new_room_id.to_string(), &0)?;
+    BANKROLL.save(deps.storage, &new_room_id.to_string(), &Bankroll::default())?;
 
     Ok(Response::new().add_attribute("action", "add_room"))
 }
 
+fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let msg: ReceiveMsg = from_binary(&wrapper.msg)?;
+    //the sender is the token contract, the amount is what it actually transferred
+    let received = Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    };
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&wrapper.sender)?,
+        funds: vec![],
+    };
+    match msg {
+        ReceiveMsg::Bet {
+            room_id,
+            bet_info,
+            replace,
+        } => execute_bet(
+            deps,
+            env,
+            info,
+            Some(received),
+            room_id,
+            bet_info,
+            BetChange::from_replace(replace),
+        ),
+        ReceiveMsg::ModifyBet { room_id, bet_info } => execute_bet(
+            deps,
+            env,
+            info,
+            Some(received),
+            room_id,
+            bet_info,
+            BetChange::Modify,
+        ),
+        ReceiveMsg::Deposit { room_id } => {
+            let amount = received.amount;
+            execute_deposit(deps, env, info, Some(received), room_id, amount)
+        }
+    }
+}
+
+/// How the chips sent to `execute_bet` change the bets the player already has in the round
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BetChange {
+    /// the chips join the bets, their whole stake is sent
+    Add,
+    /// the chips replace the bets, their whole stake is sent and the replaced stake is refunded
+    Replace,
+    /// the chips replace the bets, only the difference of the stakes is sent or refunded
+    Modify,
+    /// the bets are removed and their stake is refunded
+    Cancel,
+}
+
+impl BetChange {
+    fn from_replace(replace: Option<bool>) -> Self {
+        if replace.unwrap_or(false) {
+            BetChange::Replace
+        } else {
+            BetChange::Add
+        }
+    }
+
+    fn action(&self) -> &'static str {
+        match self {
+            BetChange::Add | BetChange::Replace => "bet",
+            BetChange::Modify => "modify_bet",
+            BetChange::Cancel => "cancel_bet",
+        }
+    }
+}
+
 fn execute_bet(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    received: Option<Cw20CoinVerified>,
     room_id: u64,
     bet_info: Vec<BetConfig>,
+    change: BetChange,
 ) -> Result<Response, ContractError> {
     let player = info.sender;
     let crr_time = env.block.time.seconds();
-    let state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    let living_round = state.living_round;
-    //validate if this room is avaialble.
 
-    let round_start_time = ROUND_START_SECOND.may_load(deps.storage, &living_round.to_string())?;
-    match round_start_time {
-        None => {}
-        Some(round_start_time) => {
-            if crr_time - round_start_time > config.next_round_seconds {
-                return Err(ContractError::RoundFinished {});
-            }
-        }
+    let mut bet_info_attributes: Vec<Attribute> = Vec::new();
+    for bet in &bet_info {
+        bet_info_attributes.push(attr("amount", bet.amount));
+        bet_info_attributes.push(attr("direction", bet.direction.clone()));
     }
 
+    let BetPlan {
+        room_info,
+        living_round,
+        round_start_time,
+        combined_bet_info,
+        imprisoned,
+        combined_amount,
+        paid_amount,
+        refund_amount,
+        bankroll,
+        exposure,
+    } = validate_bet(deps.as_ref(), crr_time, &player, room_id, bet_info, change)?;
+    //validate the input amount for the case the input denom is native token
+    validate_input_amount(
+        &info.funds,
+        received.as_ref(),
+        paid_amount,
+        &room_info.game_denom,
+    )?;
+
     //we can close the round after the first bet
-    let round_start_second =
-        ROUND_START_SECOND.may_load(deps.storage, &living_round.to_string())?;
+    if round_start_time.is_none() {
+        start_round(
+            deps.storage,
+            &config,
+            &room_info,
+            room_id,
+            living_round,
+            crr_time,
+        )?;
+    }
 
-    if round_start_second.is_none() {
-        ROUND_START_SECOND.save(deps.storage, &living_round.to_string(), &crr_time)?;
+    ROUND_EXPOSURE.save(deps.storage, round_key(room_id, living_round), &exposure)?;
+    BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+
+    //save user bet info, the imprisoned bets of a cancelled player still wait for the round
+    let bet_info_key = bet_info_key(room_id, living_round, &player);
+    if combined_bet_info.is_empty() && imprisoned.is_empty() {
+        bet_info_storage().remove(deps.storage, bet_info_key)?;
+    } else {
+        bet_info_storage().save(
+            deps.storage,
+            bet_info_key,
+            &BetInfo {
+                player: player.to_string(),
+                round_id: living_round.to_string(),
+                room_id: room_id.to_string(),
+                bet_info: combined_bet_info,
+                bet_time: crr_time,
+                imprisoned,
+            },
+        )?;
     }
 
-    let room_info = ROOMS.load(deps.storage, &room_id.to_string())?;
-    let contract_address = env.contract.address;
+    //the stake is the total the player has on the table after this change
+    let mut response = Response::new()
+        .add_attributes(vec![
+            attr("action", change.action()),
+            attr("room_id", room_id.to_string()),
+            attr("round_id", living_round.to_string()),
+            attr("player", player.to_string()),
+            attr("replace", (change != BetChange::Add).to_string()),
+            attr("stake", combined_amount),
+            attr("paid", paid_amount),
+        ])
+        .add_attributes(bet_info_attributes);
+    if !refund_amount.is_zero() {
+        let refund_msg = match &room_info.game_denom {
+            AssetInfo::Token { contract_addr } => {
+                get_cw20_transfer_msg(contract_addr, &player, refund_amount)?
+            }
+            AssetInfo::NativeToken { denom } => {
+                get_bank_transfer_to_msg(&player, denom, refund_amount)?
+            }
+        };
+        response = response
+            .add_attribute("refund", refund_amount)
+            .add_message(refund_msg);
+    }
+    Ok(response)
+}
 
-    //for the token transfer
-    let mut total_bet_amount = Uint128::zero();
-    let mut total_point = 0;
+/// Starts the round at `crr_time`, from then on it can be closed once its time is over
+fn start_round(
+    storage: &mut dyn Storage,
+    config: &Config,
+    room_info: &RoomConfig,
+    room_id: u64,
+    round_id: u64,
+    crr_time: u64,
+) -> StdResult<()> {
+    ROUND_START_SECOND.save(storage, round_key(room_id, round_id), &crr_time)?;
+
+    //bind the round to a drand beacon that nobody knows until betting is closed
+    if let RandomnessSource::Drand {
+        genesis_time,
+        period,
+        ..
+    } = config.randomness
+    {
+        let round_close = room_info.round_close(crr_time);
+        let drand_round = drand_round_after(round_close, genesis_time, period);
+        ROUND_DRAND.save(storage, round_key(room_id, round_id), &drand_round)?;
+    }
+    Ok(())
+}
 
-    let mut bet_info_attributes: Vec<Attribute> = Vec::new();
-    for bet in &bet_info {
-        bet_info_attributes.push(attr("amount", bet.amount));
-        bet_info_attributes.push(attr("direction", bet.direction.clone()));
-        total_bet_amount = total_bet_amount + bet.amount;
-        let point_info = get_points_ratio_information(&bet.direction)?;
-        total_point = total_point + point_info.points.len();
+/// Bets of a player in the living round once a change is applied, and the bankroll and exposure
+/// of the room with them
+struct BetPlan {
+    room_info: RoomConfig,
+    living_round: u64,
+    round_start_time: Option<u64>,
+    combined_bet_info: Vec<BetConfig>,
+    imprisoned: Vec<BetConfig>,
+    combined_amount: Uint128,
+    paid_amount: Uint128,
+    refund_amount: Uint128,
+    bankroll: Bankroll,
+    exposure: Exposure,
+}
+
+/// Runs every check of a bet change without storing anything, only the funds sent with it are
+/// left to the caller
+fn validate_bet(
+    deps: Deps,
+    crr_time: u64,
+    player: &Addr,
+    room_id: u64,
+    bet_info: Vec<BetConfig>,
+    change: BetChange,
+) -> Result<BetPlan, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    //validate if this room is avaialble.
+    let room_info = validate_room_id(deps, room_id)?;
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    if SETTLEMENT.has(deps.storage, &room_id.to_string()) {
+        return Err(ContractError::RoundSettling {});
+    }
+
+    //the winner must be fixed by the operator before anyone can bet
+    if config.randomness == (RandomnessSource::CommitReveal {})
+        && !ROUND_COMMITMENT.has(deps.storage, round_key(room_id, living_round))
+    {
+        return Err(ContractError::RoundNotCommitted {});
+    }
+
+    let round_start_time =
+        ROUND_START_SECOND.may_load(deps.storage, round_key(room_id, living_round))?;
+    if let Some(round_start_time) = round_start_time {
+        let betting_close = room_info.betting_close(round_start_time);
+        if crr_time > room_info.round_close(round_start_time) {
+            return Err(ContractError::RoundFinished {});
+        }
+        if crr_time > betting_close {
+            return Err(ContractError::NoMoreBets {});
+        }
+        //the bets on the table can not move anymore when the betting is about to close
+        if change != BetChange::Add && crr_time + room_info.bet_lock_seconds > betting_close {
+            return Err(ContractError::BetsLocked {});
+        }
+    }
+
+    //the new chips join the bets the player already has in this round
+    let prev_bet =
+        bet_info_storage().may_load(deps.storage, bet_info_key(room_id, living_round, player))?;
+    let (prev_bet_info, imprisoned) = match prev_bet {
+        Some(prev_bet) => (prev_bet.bet_info, prev_bet.imprisoned),
+        None => (vec![], vec![]),
+    };
+    if prev_bet_info.is_empty() && (change == BetChange::Modify || change == BetChange::Cancel) {
+        return Err(ContractError::NoBet {});
+    }
+    let total_bet_amount: Uint128 = bet_info.iter().map(|bet| bet.amount).sum();
+    let placed_payouts = pocket_payouts(&bet_info, &room_info)?;
+    let prev_amount: Uint128 = prev_bet_info.iter().map(|bet| bet.amount).sum();
+    let removed_bet_info = prev_bet_info.clone();
+    //the bets left on the table, the stake the player sends and the stake going back to them
+    let (combined_bet_info, paid_amount, refund_amount) = match change {
+        BetChange::Add => (
+            [prev_bet_info, bet_info].concat(),
+            total_bet_amount,
+            Uint128::zero(),
+        ),
+        BetChange::Replace => (bet_info, total_bet_amount, prev_amount),
+        BetChange::Modify => (
+            bet_info,
+            total_bet_amount.saturating_sub(prev_amount),
+            prev_amount.saturating_sub(total_bet_amount),
+        ),
+        BetChange::Cancel => (vec![], Uint128::zero(), prev_amount),
+    };
+
+    let mut combined_amount = Uint128::zero();
+    let mut total_point = 0;
+    for bet in &combined_bet_info {
+        combined_amount += bet.amount;
+        for (point_info, _) in get_bet_chips(bet, &room_info)? {
+            total_point += point_info.points.len();
+        }
     }
 
     if total_point > MAXIMUM_SELECT {
@@ -188,169 +513,774 @@ fn execute_bet(
     }
 
     //check if this game is haulted or not
-    assert_not_haulted(deps.as_ref())?;
-    //check the min and maximum limit for game bit
-    assert_min_max_limit(total_bet_amount, &room_info)?;
-    //user can only bet once on round for the same room
-    assert_not_double_bet(deps.as_ref(), room_id, living_round, &player)?;
-    //validate the input amount for the case the input denom is native token
-    validate_input_amount(&info.funds, total_bet_amount, &room_info.game_denom)?;
+    assert_not_haulted(deps)?;
+    //check the min and maximum limit for game bit, nothing is left on the table after a cancel
+    if change != BetChange::Cancel {
+        assert_min_max_limit(combined_amount, &room_info)?;
+    }
+
+    //the sent stake joins the bankroll of the room, and the refunded stake leaves it
+    let mut bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    if let Some(risk_fraction) = room_info.risk_fraction {
+        let max_payout = bankroll.deposited.saturating_sub(bankroll.locked) * risk_fraction;
+        for (pocket, payout) in placed_payouts.into_iter().enumerate() {
+            if payout > max_payout {
+                return Err(ContractError::RiskLimitExceeded {
+                    pocket: pocket as u32,
+                    payout,
+                    max_payout,
+                });
+            }
+        }
+    }
+    bankroll.deposited = bankroll.deposited + paid_amount - refund_amount;
     //check if the user's maximum reward can exceed on the pool limit
-    let withdraw_limit_for_admin = validate_maximum_reward_exceed(
-        deps.as_ref(),
-        &contract_address,
-        room_id,
-        living_round,
-        &bet_info,
-        &room_info.game_denom,
+    let mut exposure = load_round_exposure(deps.storage, room_id, living_round, &room_info)?;
+    update_exposure(
+        &mut exposure,
+        &removed_bet_info,
+        &combined_bet_info,
+        &room_info,
+        bankroll.deposited,
     )?;
+    bankroll.locked = exposure.reserve();
 
-    MINIMUMRESERVE.save(deps.storage, &withdraw_limit_for_admin)?;
+    Ok(BetPlan {
+        room_info,
+        living_round,
+        round_start_time,
+        combined_bet_info,
+        imprisoned,
+        combined_amount,
+        paid_amount,
+        refund_amount,
+        bankroll,
+        exposure,
+    })
+}
 
-    let bet_info_key = bet_info_key(room_id, state.living_round, &player);
-    //save user bet info
-    bet_info_storage().save(
-        deps.storage,
-        bet_info_key,
-        &BetInfo {
-            player: player.to_string(),
-            round_id: state.living_round.to_string(),
-            room_id: room_id.to_string(),
-            bet_info: bet_info.clone(),
-            bet_time: crr_time,
-        },
-    )?;
+/// Checks `player` could bet `bet_info` now, on top of the bets they already have in the round
+pub fn simulate_bet(
+    deps: Deps,
+    crr_time: u64,
+    player: &Addr,
+    room_id: u64,
+    bet_info: Vec<BetConfig>,
+) -> Result<(), ContractError> {
+    validate_bet(deps, crr_time, player, room_id, bet_info, BetChange::Add).map(|_| ())
+}
 
-    match room_info.game_denom.clone() {
-        AssetInfo::Token { contract_addr } => {
-            let cw20_transfer_from_msg = get_cw20_transfer_from_msg(
-                &contract_addr,
-                &player,
-                &contract_address,
-                total_bet_amount,
-            )?;
+fn execute_commit_round(
+    deps: DepsMut,
+    info: MessageInfo,
+    room_id: u64,
+    commitment: Binary,
+) -> Result<Response, ContractError> {
+    assert_is_distributor(deps.as_ref(), info)?;
 
-            Ok(Response::new()
-                .add_attributes(vec![
-                    attr("action", "bet"),
-                    attr("room_id", room_id.to_string()),
-                ])
-                .add_attributes(bet_info_attributes)
-                .add_message(cw20_transfer_from_msg))
-        }
-        AssetInfo::NativeToken { denom: _denom } => Ok(Response::new()
-            .add_attributes(vec![
-                attr("action", "bet"),
-                attr("room_id", room_id.to_string()),
-            ])
-            .add_attributes(bet_info_attributes)),
+    let config = CONFIG.load(deps.storage)?;
+    if config.randomness != (RandomnessSource::CommitReveal {}) {
+        return Err(ContractError::WrongRandomnessSource {});
+    }
+
+    if commitment.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    validate_room_id(deps.as_ref(), room_id)?;
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    if ROUND_COMMITMENT.has(deps.storage, round_key(room_id, living_round)) {
+        return Err(ContractError::RoundAlreadyCommitted {});
     }
+
+    ROUND_COMMITMENT.save(deps.storage, round_key(room_id, living_round), &commitment)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "commit_round")
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("round_id", living_round.to_string())
+        .add_attribute("commitment", commitment.to_base64()))
 }
 
-fn execute_close(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+fn execute_close(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    room_id: u64,
+    secret: Option<Binary>,
+    beacon: Option<DrandBeacon>,
+) -> Result<Response, ContractError> {
     let crr_time = env.block.time.seconds();
-    let state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    let living_round = state.living_round;
+    let room_info = validate_room_id(deps.as_ref(), room_id)?;
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
 
-    let round_start_time = ROUND_START_SECOND.may_load(deps.storage, &living_round.to_string())?;
-    match round_start_time {
+    let round_start_time =
+        ROUND_START_SECOND.may_load(deps.storage, round_key(room_id, living_round))?;
+    let round_start_time = match round_start_time {
         None => return Err(ContractError::RoundNotStarted {}),
-        Some(round_start_time) => {
-            if crr_time - round_start_time < config.next_round_seconds {
-                return Err(ContractError::RoundNotFinished {});
+        Some(round_start_time) => round_start_time,
+    };
+    if crr_time < room_info.round_close(round_start_time) {
+        return Err(ContractError::RoundNotFinished {});
+    }
+
+    //the proxy answers asynchronously, so the round is only settled on its callback
+    if let RandomnessSource::Proxy { contract } = &config.randomness {
+        if secret.is_some() || beacon.is_some() {
+            return Err(ContractError::WrongRandomnessSource {});
+        }
+        let drawing_since =
+            ROUND_DRAWING.may_load(deps.storage, round_key(room_id, living_round))?;
+        return match drawing_since {
+            None => {
+                ROUND_DRAWING.save(deps.storage, round_key(room_id, living_round), &crr_time)?;
+                ROUND_KEEPER.save(deps.storage, round_key(room_id, living_round), &info.sender)?;
+                let request_msg = WasmMsg::Execute {
+                    contract_addr: contract.to_string(),
+                    msg: to_binary(&RandomnessProxyExecuteMsg::GetNextRandomness {
+                        job_id: format!("{}/{}", room_id, living_round),
+                    })?,
+                    funds: vec![],
+                };
+                Ok(Response::new()
+                    .add_attribute("action", "request_randomness")
+                    .add_attribute("room_id", room_id.to_string())
+                    .add_attribute("round_id", living_round.to_string())
+                    .add_message(request_msg))
+            }
+            Some(drawing_since) => {
+                //the proxy never answered, so nobody wins and every bet goes back to its player,
+                //this trusts the proxy to answer in time, see `RandomnessSource::Proxy`
+                if crr_time < drawing_since + config.reveal_timeout_seconds {
+                    return Err(ContractError::RoundDrawing {});
+                }
+                void_round(deps, crr_time, room_id, living_round)
+            }
+        };
+    }
+
+    match get_round_proof(
+        deps.as_ref(),
+        &config,
+        room_id,
+        living_round,
+        secret,
+        beacon,
+    )? {
+        Some(proof) => {
+            ROUND_KEEPER.save(deps.storage, round_key(room_id, living_round), &info.sender)?;
+            settle_round(deps, room_id, living_round, proof)
+        }
+        None => {
+            //the operator missed the reveal, so nobody wins and every bet goes back to its player
+            let reveal_deadline =
+                room_info.round_close(round_start_time) + config.reveal_timeout_seconds;
+            if crr_time < reveal_deadline {
+                return Err(ContractError::RevealPending {});
             }
+            assert_beacon_not_due(deps.as_ref(), &config, crr_time, room_id, living_round)?;
+            void_round(deps, crr_time, room_id, living_round)
+        }
+    }
+}
+
+fn execute_receive_randomness(
+    deps: DepsMut,
+    info: MessageInfo,
+    callback: RandomnessCallback,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    match &config.randomness {
+        RandomnessSource::Proxy { contract } if &info.sender == contract => {}
+        _ => return Err(ContractError::Unauthorized {}),
+    }
+
+    //job ids are "room_id/round_id" and only the living round of a room can be drawing
+    let (room_id, round_id) = match parse_job_id(&callback.job_id) {
+        Some(ids) => ids,
+        None => {
+            return Err(ContractError::UnknownRandomnessJob {
+                job_id: callback.job_id,
+            })
         }
+    };
+    let living_round = LIVING_ROUND.may_load(deps.storage, &room_id.to_string())?;
+    if living_round != Some(round_id)
+        || !ROUND_DRAWING.has(deps.storage, round_key(room_id, round_id))
+    {
+        return Err(ContractError::UnknownRandomnessJob {
+            job_id: callback.job_id,
+        });
     }
 
-    let winner = rand_generator(&info, &env);
+    let proof = RoundProof {
+        seed: Binary::from(callback.randomness.to_vec()),
+        entropy: round_entropy(room_id, round_id),
+        commitment: None,
+        beacon: None,
+        job_id: Some(callback.job_id),
+        pockets: 0,
+        winner: 0,
+    };
+    settle_round(deps, room_id, round_id, proof)
+}
+
+fn parse_job_id(job_id: &str) -> Option<(u64, u64)> {
+    let (room_id, round_id) = job_id.split_once('/')?;
+    Some((room_id.parse().ok()?, round_id.parse().ok()?))
+}
+
+/// Entropy mixed into the seed, so rooms sharing a seed source still draw independently.
+fn round_entropy(room_id: u64, round_id: u64) -> Binary {
+    let mut entropy = room_id.to_be_bytes().to_vec();
+    entropy.extend_from_slice(&round_id.to_be_bytes());
+    Binary::from(entropy)
+}
 
-    // assert_is_distributor(deps.as_ref(), info)?;
+/// Draws the winner of the round from the verified seed,
+/// the winners are credited by `SettleRound` afterwards.
+fn settle_round(
+    deps: DepsMut,
+    room_id: u64,
+    round_id: u64,
+    mut proof: RoundProof,
+) -> Result<Response, ContractError> {
+    let room_info = ROOMS.load(deps.storage, &room_id.to_string())?;
+    proof.pockets = room_info.wheel.pockets();
+    let winner = rand_generator(&proof.seed, &proof.entropy, proof.pockets);
+    proof.winner = winner;
 
-    WINNERNUMBER.save(deps.storage, &living_round.to_string(), &winner)?;
+    WINNERNUMBER.save(deps.storage, round_key(room_id, round_id), &winner)?;
+    ROUND_PROOF.save(deps.storage, round_key(room_id, round_id), &proof)?;
     //Update the living round
-    STATE.update(deps.storage, |mut state| -> StdResult<_> {
-        state.living_round = state.living_round + 1;
-        Ok(state)
-    })?;
+    LIVING_ROUND.save(deps.storage, &room_id.to_string(), &(round_id + 1))?;
 
-    let transfer_messages: Vec<CosmosMsg> =
-        distribute_reward_to_users(deps.as_ref(), living_round, state.room_id, winner)?;
-    MINIMUMRESERVE.save(deps.storage, &Uint128::new(0))?;
+    start_settlement(deps, room_id, round_id, Some(winner))?;
 
     Ok(Response::new()
         .add_attribute("action", "close_round")
         .add_attribute("winner", winner.to_string())
-        .add_attribute("round_id", living_round.to_string())
-        .add_messages(transfer_messages))
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("round_id", round_id.to_string()))
+}
+
+/// Closes the round without a winner, every stake is refunded by `SettleRound` afterwards.
+fn void_round(
+    deps: DepsMut,
+    crr_time: u64,
+    room_id: u64,
+    round_id: u64,
+) -> Result<Response, ContractError> {
+    VOID_ROUNDS.save(deps.storage, round_key(room_id, round_id), &crr_time)?;
+    LIVING_ROUND.save(deps.storage, &room_id.to_string(), &(round_id + 1))?;
+    start_settlement(deps, room_id, round_id, None)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "void_round")
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("round_id", round_id.to_string()))
 }
 
-fn distribute_reward_to_users(
+/// Voids the living round when nobody closed it or its randomness never came,
+/// so the players get their stakes back and the reserve locked for it is released.
+fn execute_refund_round(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    room_id: u64,
+    round_id: u64,
+) -> Result<Response, ContractError> {
+    let crr_time = env.block.time.seconds();
+    let config = CONFIG.load(deps.storage)?;
+    let room_info = validate_room_id(deps.as_ref(), room_id)?;
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    if round_id != living_round {
+        return Err(ContractError::RoundClosed { round_id });
+    }
+
+    let round_start_time = ROUND_START_SECOND
+        .may_load(deps.storage, round_key(room_id, round_id))?
+        .ok_or(ContractError::RoundNotStarted {})?;
+    if crr_time < room_info.round_close(round_start_time) + config.refund_timeout_seconds {
+        return Err(ContractError::RefundPending {});
+    }
+    assert_beacon_not_due(deps.as_ref(), &config, crr_time, room_id, round_id)?;
+    if !bet_info_storage().has(deps.storage, bet_info_key(room_id, round_id, &info.sender)) {
+        return Err(ContractError::NoBet {});
+    }
+
+    let response = void_round(deps.branch(), crr_time, room_id, round_id)?;
+    //small rounds are refunded at once, the rest is left to `SettleRound`
+    let settlement = SETTLEMENT.load(deps.storage, &room_id.to_string())?;
+    let done = settle_bets(deps, crr_time, room_id, settlement, DEFAULT_SETTLE_LIMIT)?;
+
+    Ok(response
+        .add_attribute("refunded_by", info.sender)
+        .add_attribute("done", done.to_string()))
+}
+
+/// Validates the randomness submitted on close against the configured source.
+/// Returns none when nothing is submitted, which voids the round once the reveal timeout is over
+/// and, with drand, only while the beacon of the round is not due.
+fn get_round_proof(
     deps: Deps,
+    config: &Config,
+    room_id: u64,
     round_id: u64,
-    last_room_id: u64,
-    winner: u32,
-) -> StdResult<Vec<CosmosMsg>> {
-    let mut transfer_msgs: Vec<CosmosMsg> = Vec::new();
+    secret: Option<Binary>,
+    beacon: Option<DrandBeacon>,
+) -> Result<Option<RoundProof>, ContractError> {
+    let entropy = round_entropy(room_id, round_id);
+    match &config.randomness {
+        RandomnessSource::CommitReveal {} => {
+            if beacon.is_some() {
+                return Err(ContractError::WrongRandomnessSource {});
+            }
+            match secret {
+                Some(secret) => {
+                    let commitment =
+                        ROUND_COMMITMENT.load(deps.storage, round_key(room_id, round_id))?;
+                    if sha_256(secret.as_slice()) != commitment.as_slice() {
+                        return Err(ContractError::InvalidReveal {});
+                    }
+                    Ok(Some(RoundProof {
+                        seed: secret,
+                        entropy,
+                        commitment: Some(commitment),
+                        beacon: None,
+                        job_id: None,
+                        pockets: 0,
+                        winner: 0,
+                    }))
+                }
+                None => Ok(None),
+            }
+        }
+        RandomnessSource::Proxy { .. } => Ok(None),
+        RandomnessSource::Drand { pubkey, .. } => {
+            if secret.is_some() {
+                return Err(ContractError::WrongRandomnessSource {});
+            }
+            match beacon {
+                Some(beacon) => {
+                    let expected = ROUND_DRAND.load(deps.storage, round_key(room_id, round_id))?;
+                    if beacon.round != expected {
+                        return Err(ContractError::WrongDrandRound {
+                            expected,
+                            provided: beacon.round,
+                        });
+                    }
+                    if !verify_beacon(pubkey, beacon.round, &beacon.signature) {
+                        return Err(ContractError::InvalidBeacon {});
+                    }
+                    Ok(Some(RoundProof {
+                        seed: Binary::from(beacon_randomness(&beacon.signature).to_vec()),
+                        entropy,
+                        commitment: None,
+                        beacon: Some(beacon),
+                        job_id: None,
+                        pockets: 0,
+                        winner: 0,
+                    }))
+                }
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// A drand beacon tells the winner to anyone once it is due, so a round bound to it can not be
+/// voided anymore, or a losing player could void every round they lost
+fn assert_beacon_not_due(
+    deps: Deps,
+    config: &Config,
+    crr_time: u64,
+    room_id: u64,
+    round_id: u64,
+) -> Result<(), ContractError> {
+    if let RandomnessSource::Drand {
+        genesis_time,
+        period,
+        ..
+    } = config.randomness
+    {
+        if let Some(drand_round) =
+            ROUND_DRAND.may_load(deps.storage, round_key(room_id, round_id))?
+        {
+            if crr_time >= drand_round_time(drand_round, genesis_time, period) {
+                return Err(ContractError::BeaconPublished { drand_round });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn start_settlement(
+    deps: DepsMut,
+    room_id: u64,
+    round_id: u64,
+    winner: Option<u32>,
+) -> StdResult<()> {
+    SETTLEMENT.save(
+        deps.storage,
+        &room_id.to_string(),
+        &Settlement {
+            round_id,
+            winner,
+            cursor: None,
+            total_bet_amount: Uint128::zero(),
+            user_winning_amount: Uint128::zero(),
+            imprisoned_amount: Uint128::zero(),
+        },
+    )
+}
 
+fn execute_settle_round(
+    deps: DepsMut,
+    env: Env,
+    room_id: u64,
+    round_id: u64,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    validate_room_id(deps.as_ref(), room_id)?;
+    let settlement = SETTLEMENT.may_load(deps.storage, &room_id.to_string())?;
+    let settlement = match settlement {
+        Some(settlement) if settlement.round_id == round_id => settlement,
+        _ => return Err(ContractError::NoPendingSettlement { round_id }),
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_SETTLE_LIMIT).min(MAX_SETTLE_LIMIT);
+    let done = settle_bets(deps, env.block.time.seconds(), room_id, settlement, limit)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "settle_round")
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("round_id", round_id.to_string())
+        .add_attribute("done", done.to_string()))
+}
+
+/// Credits the bets of the next `limit` players of the round to their claimable balances.
+/// Once every bet is credited the platform fee is owed to the distributor and the room accepts
+/// bets again. Returns whether the round is fully settled.
+fn settle_bets(
+    deps: DepsMut,
+    crr_time: u64,
+    room_id: u64,
+    mut settlement: Settlement,
+    limit: u32,
+) -> StdResult<bool> {
     let config = CONFIG.load(deps.storage)?;
-    //on a room basis, we will calculate the reward because the bet denom is different from each room.
-    for i in 1..last_room_id + 1 {
-        let room_id = i;
-        let room_info = ROOMS.load(deps.storage, &room_id.to_string())?;
-        let mut total_bet_amount = Uint128::zero();
-        let mut user_winning_amount = Uint128::zero();
-        //get player list for this room and this round_id
-        let players_info = query_all_members_one_round_room(deps, room_id, round_id)?;
-        for player_info in players_info.bets_info {
-            //for each users, he can do several bets for one transaction
-            for bet in &player_info.bet_info {
-                total_bet_amount = total_bet_amount + bet.amount;
-                let point_ratio_info = get_points_ratio_information(&bet.direction)?;
-                let index = point_ratio_info.points.iter().position(|&x| x == winner);
-                if index.is_some() {
-                    let reward_without_fee =
-                        bet.amount * Uint128::new(point_ratio_info.ratio as u128);
-                    user_winning_amount = user_winning_amount + reward_without_fee;
-                    let reward = reward_without_fee * (Decimal::one() - config.platform_fee);
-                    let recipient = &deps.api.addr_validate(&player_info.player)?;
-                    let transfer_msg = match &room_info.game_denom {
-                        AssetInfo::Token { contract_addr } => {
-                            get_cw20_transfer_msg(contract_addr, recipient, reward)?
+    let room_info = ROOMS.load(deps.storage, &room_id.to_string())?;
+    let round_id = settlement.round_id;
+    let start = settlement
+        .cursor
+        .as_ref()
+        .map(|player| Bound::exclusive(bet_info_key(room_id, round_id, &Addr::unchecked(player))));
+    //one more player is loaded to know if this is the last batch
+    let players_info = bet_info_storage()
+        .idx
+        .room_round_players
+        .prefix((room_id.to_string(), round_id.to_string()))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit as usize + 1)
+        .map(|item| item.map(|(_, b)| b))
+        .collect::<StdResult<Vec<_>>>()?;
+    let done = players_info.len() <= limit as usize;
+
+    for player_info in players_info.into_iter().take(limit as usize) {
+        let mut player_credit = Uint128::zero();
+        let mut imprisoned = vec![];
+        //for each users, he can do several bets for one transaction
+        for bet in &player_info.bet_info {
+            match settlement.winner {
+                Some(winner)
+                    if room_info.wheel.is_zero(winner) && is_even_money(&bet.direction) =>
+                {
+                    match room_info.zero_rule {
+                        ZeroRule::Standard => settlement.total_bet_amount += bet.amount,
+                        //only the half kept by the room is a bet of this round
+                        ZeroRule::LaPartage => {
+                            let half = bet.amount.multiply_ratio(1u128, 2u128);
+                            settlement.total_bet_amount += bet.amount - half;
+                            player_credit += half;
                         }
-                        AssetInfo::NativeToken { denom } => {
-                            get_bank_transfer_to_msg(recipient, denom, reward)?
+                        //the stake stays locked until the next round decides it
+                        ZeroRule::EnPrison => {
+                            settlement.imprisoned_amount += bet.amount;
+                            imprisoned.push(bet.clone());
                         }
-                    };
-                    transfer_msgs.push(transfer_msg);
+                    }
+                }
+                Some(winner) => {
+                    settlement.total_bet_amount += bet.amount;
+                    //every chip of the bet is paid by its own ratio
+                    for (point_ratio_info, amount) in get_bet_chips(bet, &room_info)? {
+                        if point_ratio_info.points.contains(&winner) {
+                            let reward_without_fee = amount * point_ratio_info.ratio;
+                            settlement.user_winning_amount += reward_without_fee;
+                            player_credit +=
+                                reward_without_fee * (Decimal::one() - config.platform_fee);
+                        }
+                    }
+                }
+                //the round is voided, so the stake goes back to the player
+                None => player_credit += bet.amount,
+            }
+        }
+        //an imprisoned bet returns its stake if it wins, and is lost otherwise
+        for bet in &player_info.imprisoned {
+            match settlement.winner {
+                Some(winner) => {
+                    let point_ratio_info =
+                        get_points_ratio_information(&bet.direction, &room_info)?;
+                    if point_ratio_info.points.contains(&winner) {
+                        player_credit += bet.amount;
+                    } else {
+                        settlement.total_bet_amount += bet.amount;
+                    }
                 }
+                None => player_credit += bet.amount,
+            }
+        }
+        if !player_credit.is_zero() {
+            credit_claimable(deps.storage, &player_info.player, room_id, player_credit)?;
+        }
+        if !imprisoned.is_empty() {
+            //the imprisoned bets start the next round, so it can be closed without new bets
+            if !ROUND_START_SECOND.has(deps.storage, round_key(room_id, round_id + 1)) {
+                start_round(
+                    deps.storage,
+                    &config,
+                    &room_info,
+                    room_id,
+                    round_id + 1,
+                    crr_time,
+                )?;
             }
+            add_imprisoned_exposure(deps.storage, room_id, round_id + 1, &room_info, &imprisoned)?;
+            bet_info_storage().save(
+                deps.storage,
+                bet_info_key(room_id, round_id + 1, &Addr::unchecked(&player_info.player)),
+                &BetInfo {
+                    player: player_info.player.clone(),
+                    round_id: (round_id + 1).to_string(),
+                    room_id: room_id.to_string(),
+                    bet_info: vec![],
+                    bet_time: player_info.bet_time,
+                    imprisoned,
+                },
+            )?;
         }
+        settlement.cursor = Some(player_info.player);
+    }
+
+    if !done {
+        SETTLEMENT.save(deps.storage, &room_id.to_string(), &settlement)?;
+        return Ok(false);
+    }
+    SETTLEMENT.remove(deps.storage, &room_id.to_string());
+    let mut bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    //the imprisoned stakes may have to be returned by the next round
+    bankroll.locked = settlement.imprisoned_amount;
+    if settlement.winner.is_none() {
+        BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+        process_withdrawal_queue(deps.storage, room_id)?;
+        return Ok(true);
+    }
+
+    //check game fee
+    //first check for winners fee
+    let total_bet_amount = settlement.total_bet_amount;
+    let user_winning_amount = settlement.user_winning_amount;
+    let mut game_fee = user_winning_amount * config.platform_fee;
+
+    //second check for the admin
+    //send some percent of round reward to the admin as platform fee.
+    if total_bet_amount > user_winning_amount {
+        let reward_for_admin_side = total_bet_amount - user_winning_amount;
+        game_fee += reward_for_admin_side * config.platform_fee;
+    }
 
-        //check game fee
-        //first check for winners fee
-        let mut game_fee = user_winning_amount * config.platform_fee;
+    //the keeper who closed the round gets its share of the fee like a winning
+    let keeper = ROUND_KEEPER.may_load(deps.storage, round_key(room_id, round_id))?;
+    let keeper_fee = match (&keeper, room_info.keeper_reward) {
+        (Some(_), Some(keeper_reward)) => game_fee * keeper_reward,
+        _ => Uint128::zero(),
+    };
 
-        //second check for the admin
-        //send some percent of round reward to the admin as platform fee.
-        if total_bet_amount > user_winning_amount {
-            let reward_for_admin_side = total_bet_amount - user_winning_amount;
-            game_fee = game_fee + reward_for_admin_side * config.platform_fee;
+    //the fee stays in the contract until the distributor collects it
+    bankroll.deposited = bankroll.deposited.checked_sub(game_fee - keeper_fee)?;
+    bankroll.fees_owed += game_fee - keeper_fee;
+    BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+    if let Some(keeper) = keeper {
+        if !keeper_fee.is_zero() {
+            credit_claimable(deps.storage, keeper.as_str(), room_id, keeper_fee)?;
         }
+    }
+    process_withdrawal_queue(deps.storage, room_id)?;
+    Ok(true)
+}
 
-        if game_fee > Uint128::zero() {
-            let transfer_msg = match &room_info.game_denom {
-                AssetInfo::Token { contract_addr } => {
-                    get_cw20_transfer_msg(contract_addr, &config.distributor, game_fee)?
-                }
-                AssetInfo::NativeToken { denom } => {
-                    get_bank_transfer_to_msg(&config.distributor, denom, game_fee)?
-                }
-            };
+/// Pays the queued withdrawals of the room in order from its unlocked bankroll, right after a
+/// settlement released the exposure of the round. A request that does not fit is partly paid
+/// and stays first in the queue.
+fn process_withdrawal_queue(storage: &mut dyn Storage, room_id: u64) -> StdResult<()> {
+    let requests = WITHDRAWAL_QUEUE
+        .prefix(room_id.to_string())
+        .range(storage, None, None, Order::Ascending)
+        .take(MAX_WITHDRAWALS_PER_SETTLEMENT)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, mut request) in requests {
+        let mut bankroll = BANKROLL.load(storage, &room_id.to_string())?;
+        //the shares escrowed before the bankroll was depleted are worthless
+        if request.share_epoch != bankroll.share_epoch {
+            WITHDRAWAL_QUEUE.remove(storage, (room_id.to_string(), id));
+            continue;
+        }
+        let unlocked = bankroll.deposited.saturating_sub(bankroll.locked);
+        if unlocked.is_zero() {
+            break;
+        }
+        let value = bankroll
+            .deposited
+            .multiply_ratio(request.shares, bankroll.total_shares);
+        let (amount, burned_shares) = if value <= unlocked {
+            (value, request.shares)
+        } else {
+            let burned_shares = shares_for_amount(unlocked, &bankroll, true)?;
+            (unlocked, burned_shares.min(request.shares))
+        };
+        bankroll.total_shares -= burned_shares;
+        BANKROLL.save(storage, &room_id.to_string(), &bankroll)?;
+        credit_claimable(storage, request.provider.as_str(), room_id, amount)?;
+
+        request.shares -= burned_shares;
+        if !request.shares.is_zero() {
+            WITHDRAWAL_QUEUE.save(storage, (room_id.to_string(), id), &request)?;
+            break;
+        }
+        WITHDRAWAL_QUEUE.remove(storage, (room_id.to_string(), id));
+    }
+    Ok(())
+}
+
+fn execute_collect_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    room_id: u64,
+) -> Result<Response, ContractError> {
+    assert_is_distributor(deps.as_ref(), info)?;
+    let room_info = validate_room_id(deps.as_ref(), room_id)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    let game_fee = bankroll.fees_owed;
+    if game_fee.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    bankroll.fees_owed = Uint128::zero();
+    BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+
+    let transfer_msg = match &room_info.game_denom {
+        AssetInfo::Token { contract_addr } => {
+            get_cw20_transfer_msg(contract_addr, &config.distributor, game_fee)?
+        }
+        AssetInfo::NativeToken { denom } => {
+            get_bank_transfer_to_msg(&config.distributor, denom, game_fee)?
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "collect_fees")
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("amount", game_fee)
+        .add_message(transfer_msg))
+}
+
+fn credit_claimable(
+    storage: &mut dyn Storage,
+    player: &str,
+    room_id: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    CLAIMABLE.update(
+        storage,
+        (player.to_string(), room_id.to_string()),
+        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
+    )?;
+    //the credit is paid out of the bankroll of the room
+    BANKROLL.update(storage, &room_id.to_string(), |bankroll| -> StdResult<_> {
+        let mut bankroll = bankroll.unwrap_or_default();
+        bankroll.deposited = bankroll.deposited.checked_sub(amount)?;
+        Ok(bankroll)
+    })?;
+    Ok(())
+}
+
+/// Removes the claimable balance of the player in this room and returns its transfer message.
+fn take_claimable(
+    deps: DepsMut,
+    player: &Addr,
+    room_id: u64,
+    room_info: &RoomConfig,
+) -> StdResult<Option<CosmosMsg>> {
+    let key = (player.to_string(), room_id.to_string());
+    let amount = CLAIMABLE.may_load(deps.storage, key.clone())?;
+    let amount = match amount {
+        Some(amount) if !amount.is_zero() => amount,
+        _ => return Ok(None),
+    };
+    CLAIMABLE.remove(deps.storage, key);
+
+    let transfer_msg = match &room_info.game_denom {
+        AssetInfo::Token { contract_addr } => get_cw20_transfer_msg(contract_addr, player, amount)?,
+        AssetInfo::NativeToken { denom } => get_bank_transfer_to_msg(player, denom, amount)?,
+    };
+    Ok(Some(transfer_msg))
+}
+
+fn execute_claim(
+    deps: DepsMut,
+    info: MessageInfo,
+    room_id: u64,
+) -> Result<Response, ContractError> {
+    let room_info = validate_room_id(deps.as_ref(), room_id)?;
+    let transfer_msg = take_claimable(deps, &info.sender, room_id, &room_info)?;
+    match transfer_msg {
+        Some(transfer_msg) => Ok(Response::new()
+            .add_attribute("action", "claim")
+            .add_attribute("room_id", room_id.to_string())
+            .add_message(transfer_msg)),
+        None => Err(ContractError::NothingToClaim {}),
+    }
+}
+
+fn execute_claim_all(mut deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let room_ids = CLAIMABLE
+        .prefix(info.sender.to_string())
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut transfer_msgs: Vec<CosmosMsg> = Vec::new();
+    for room_id in room_ids {
+        let room_info = ROOMS.load(deps.storage, &room_id)?;
+        let room_id = room_id
+            .parse::<u64>()
+            .map_err(|_| StdError::generic_err("Invalid room id"))?;
+        if let Some(transfer_msg) =
+            take_claimable(deps.branch(), &info.sender, room_id, &room_info)?
+        {
             transfer_msgs.push(transfer_msg);
         }
     }
-    Ok(transfer_msgs)
+    if transfer_msgs.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_all")
+        .add_messages(transfer_msgs))
 }
 
 fn execute_withdraw_from_pool(
@@ -358,68 +1288,183 @@ fn execute_withdraw_from_pool(
     env: Env,
     info: MessageInfo,
     room_id: u64,
-    amount: Uint128,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let room_info = validate_room_id(deps.as_ref(), room_id)?;
+    assert_pool_open(deps.as_ref(), env.block.time.seconds(), room_id, &room_info)?;
+    let withdrawal_amount = get_provider_withdrawal_amount(deps.as_ref(), room_id, &info.sender)?;
+    if withdrawal_amount < amount {
+        return Err(ContractError::WithdrawalMoneyExceeded {
+            withdrawal_amount,
+            amount,
+        });
+    }
+
+    //the shares worth the amount are burned, rounded up in favor of the other providers
+    let mut bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    let burned_shares = shares_for_amount(amount, &bankroll, true)?;
+    bankroll.deposited -= amount;
+    bankroll.total_shares -= burned_shares;
+    BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+    LP_SHARES.update(
+        deps.storage,
+        lp_shares_key(&info.sender, room_id, bankroll.share_epoch),
+        |shares| -> StdResult<_> { Ok(shares.unwrap_or_default().checked_sub(burned_shares)?) },
+    )?;
+
+    let transfer_msg = match room_info.game_denom {
+        AssetInfo::Token { contract_addr } => {
+            get_cw20_transfer_msg(&contract_addr, &info.sender, amount)?
+        }
+        AssetInfo::NativeToken { denom } => get_bank_transfer_to_msg(&info.sender, &denom, amount)?,
+    };
+
+    Ok(Response::new()
+        .add_message(transfer_msg)
+        .add_attribute("withdrawer", info.sender.to_string())
+        .add_attribute("room_id", room_id.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("burned_shares", burned_shares))
+}
+
+fn execute_queue_withdrawal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    room_id: u64,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    validate_room_id(deps.as_ref(), room_id)?;
+    assert_not_settling(deps.as_ref(), room_id)?;
+    let share_epoch = BANKROLL
+        .load(deps.storage, &room_id.to_string())?
+        .share_epoch;
+    let provider_shares = LP_SHARES
+        .may_load(
+            deps.storage,
+            lp_shares_key(&info.sender, room_id, share_epoch),
+        )?
+        .unwrap_or_default();
+    if shares.is_zero() || shares > provider_shares {
+        return Err(ContractError::InsufficientShares {
+            shares: provider_shares,
+        });
+    }
+
+    //the escrowed shares keep their part of the profit and loss until they are paid
+    LP_SHARES.save(
+        deps.storage,
+        lp_shares_key(&info.sender, room_id, share_epoch),
+        &(provider_shares - shares),
+    )?;
+    let id = NEXT_WITHDRAWAL_ID
+        .may_load(deps.storage, &room_id.to_string())?
+        .unwrap_or_default();
+    NEXT_WITHDRAWAL_ID.save(deps.storage, &room_id.to_string(), &(id + 1))?;
+    WITHDRAWAL_QUEUE.save(
+        deps.storage,
+        (room_id.to_string(), id),
+        &WithdrawalRequest {
+            provider: info.sender.clone(),
+            shares,
+            requested_at: env.block.time.seconds(),
+            share_epoch,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "queue_withdrawal"),
+        attr("room_id", room_id.to_string()),
+        attr("provider", info.sender.to_string()),
+        attr("withdrawal_id", id.to_string()),
+        attr("shares", shares),
+    ]))
+}
+
+/// Returns the shares still escrowed by a queued withdrawal, a partly paid request keeps what
+/// it was already credited
+fn execute_cancel_withdrawal(
+    deps: DepsMut,
+    info: MessageInfo,
+    room_id: u64,
+    withdrawal_id: u64,
 ) -> Result<Response, ContractError> {
-    let room_info = validate_room_id(deps.as_ref(), room_id)?;
-    let contract_address = env.contract.address;
-    assert_is_room_owner(deps.as_ref(), &info, &room_info)?;
-    let withdrawal_amount = get_withdrawal_amount(deps.as_ref(), &room_info, &contract_address)?;
-    if withdrawal_amount < amount {
-        return Err(ContractError::WithdrawalMoneyExceeded {
-            withdrawal_amount,
-            amount,
-        });
+    let request = WITHDRAWAL_QUEUE
+        .may_load(deps.storage, (room_id.to_string(), withdrawal_id))?
+        .ok_or(ContractError::UnknownWithdrawal { withdrawal_id })?;
+    if request.provider != info.sender {
+        return Err(ContractError::Unauthorized {});
     }
 
-    let transfer_msg = match room_info.game_denom {
-        AssetInfo::Token { contract_addr } => {
-            get_cw20_transfer_msg(&contract_addr, &info.sender, amount)?
-        }
-        AssetInfo::NativeToken { denom } => get_bank_transfer_to_msg(&info.sender, &denom, amount)?,
-    };
+    WITHDRAWAL_QUEUE.remove(deps.storage, (room_id.to_string(), withdrawal_id));
+    LP_SHARES.update(
+        deps.storage,
+        lp_shares_key(&info.sender, room_id, request.share_epoch),
+        |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + request.shares) },
+    )?;
 
-    Ok(Response::new()
-        .add_message(transfer_msg)
-        .add_attribute("withdrawer", info.sender.to_string())
-        .add_attribute("room_id", room_id.to_string())
-        .add_attribute("amount", amount))
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel_withdrawal"),
+        attr("room_id", room_id.to_string()),
+        attr("provider", info.sender.to_string()),
+        attr("withdrawal_id", withdrawal_id.to_string()),
+        attr("shares", request.shares),
+    ]))
 }
 
 fn execute_deposit(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    received: Option<Cw20CoinVerified>,
     room_id: u64,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
     let room_info = validate_room_id(deps.as_ref(), room_id)?;
-    let game_denom = room_info.clone().game_denom;
-    let contract_address = env.contract.address;
+    assert_pool_open(deps.as_ref(), env.block.time.seconds(), room_id, &room_info)?;
 
-    assert_is_room_owner(deps.as_ref(), &info, &room_info)?;
-    validate_input_amount(&info.funds, amount, &game_denom)?;
-
-    let player = info.sender;
+    validate_input_amount(
+        &info.funds,
+        received.as_ref(),
+        amount,
+        &room_info.game_denom,
+    )?;
 
-    match room_info.game_denom {
-        AssetInfo::Token { contract_addr } => {
-            let cw20_transfer_from_msg =
-                get_cw20_transfer_from_msg(&contract_addr, &player, &contract_address, amount)?;
-
-            Ok(Response::new()
-                .add_attributes(vec![
-                    attr("action", "deposit"),
-                    attr("room_id", room_id.to_string()),
-                    attr("amount", amount.to_string()),
-                ])
-                .add_message(cw20_transfer_from_msg))
-        }
-        AssetInfo::NativeToken { denom: _denom } => Ok(Response::new().add_attributes(vec![
-            attr("action", "deposit"),
-            attr("room_id", room_id.to_string()),
-            attr("amount", amount.to_string()),
-        ])),
+    //the new shares are priced by the bankroll before the deposit, so the profit and loss
+    //made so far stays with the previous providers
+    let mut bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    //the shares of a depleted bankroll are worthless, the deposit starts a new epoch of shares
+    if bankroll.deposited.is_zero() && !bankroll.total_shares.is_zero() {
+        bankroll.total_shares = Uint128::zero();
+        bankroll.share_epoch += 1;
     }
+    //a bankroll left without shares belongs to the platform, not to the next provider
+    if bankroll.total_shares.is_zero() && !bankroll.deposited.is_zero() {
+        let config = CONFIG.load(deps.storage)?;
+        bankroll.total_shares = bankroll.deposited;
+        LP_SHARES.update(
+            deps.storage,
+            lp_shares_key(&config.distributor, room_id, bankroll.share_epoch),
+            |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + bankroll.deposited) },
+        )?;
+    }
+    let minted_shares = shares_for_amount(amount, &bankroll, false)?;
+    bankroll.deposited += amount;
+    bankroll.total_shares += minted_shares;
+    BANKROLL.save(deps.storage, &room_id.to_string(), &bankroll)?;
+    LP_SHARES.update(
+        deps.storage,
+        lp_shares_key(&info.sender, room_id, bankroll.share_epoch),
+        |shares| -> StdResult<_> { Ok(shares.unwrap_or_default() + minted_shares) },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deposit"),
+        attr("room_id", room_id.to_string()),
+        attr("amount", amount.to_string()),
+        attr("provider", info.sender.to_string()),
+        attr("minted_shares", minted_shares),
+    ]))
 }
 
 fn execute_change_room_config(
@@ -455,10 +1500,12 @@ fn execute_update_bet_limit(
     room_id: u64,
     max_bet: Uint128,
     min_bet: Uint128,
+    risk_fraction: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let room_info = validate_room_id(deps.as_ref(), room_id)?;
 
     assert_is_room_owner(deps.as_ref(), &info, &room_info)?;
+    validate_risk_fraction(risk_fraction)?;
 
     ROOMS.update(
         deps.storage,
@@ -467,6 +1514,7 @@ fn execute_update_bet_limit(
             let mut room_info = room_info.unwrap();
             room_info.max_bet = max_bet;
             room_info.min_bet = min_bet;
+            room_info.risk_fraction = risk_fraction;
             Ok(room_info)
         },
     )?;
@@ -478,6 +1526,44 @@ fn execute_update_bet_limit(
         .add_attribute("min_bet", min_bet))
 }
 
+fn validate_risk_fraction(risk_fraction: Option<Decimal>) -> Result<(), ContractError> {
+    match risk_fraction {
+        Some(risk_fraction) if risk_fraction.is_zero() || risk_fraction > Decimal::one() => {
+            Err(ContractError::InvalidRiskFraction {})
+        }
+        _ => Ok(()),
+    }
+}
+
+fn execute_update_payout_table(
+    deps: DepsMut,
+    info: MessageInfo,
+    room_id: u64,
+    payout_table: PayoutTable,
+) -> Result<Response, ContractError> {
+    let mut room_info = validate_room_id(deps.as_ref(), room_id)?;
+    assert_is_room_owner(deps.as_ref(), &info, &room_info)?;
+
+    //the bets of the living round were accepted with the current payouts
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    if SETTLEMENT.has(deps.storage, &room_id.to_string()) {
+        return Err(ContractError::RoundSettling {});
+    }
+    let exposure = load_round_exposure(deps.storage, room_id, living_round, &room_info)?;
+    if !exposure.total_stake.is_zero() {
+        return Err(ContractError::BetsPlaced {});
+    }
+
+    room_info.payout_table = payout_table;
+    let config = CONFIG.load(deps.storage)?;
+    validate_payout_table(&config, &room_info)?;
+    ROOMS.save(deps.storage, &room_id.to_string(), &room_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_payout_table")
+        .add_attribute("room_id", room_id.to_string()))
+}
+
 fn assert_not_haulted(deps: Deps) -> StdResult<bool> {
     let state = STATE.load(deps.storage)?;
     let is_haulted = state.is_haulted;
@@ -518,7 +1604,7 @@ fn assert_is_room_owner(deps: Deps, info: &MessageInfo, room: &RoomConfig) -> St
 
     let room_owner = nft_info.access.owner;
 
-    if info.sender.to_string() != room_owner {
+    if info.sender != room_owner {
         return Err(StdError::generic_err(format!(
             "Only the admin of room can execute this function. Room Admin: {}, Sender: {}",
             room_owner, info.sender
@@ -554,34 +1640,42 @@ fn assert_min_max_limit(total_bet_amount: Uint128, room_info: &RoomConfig) -> St
     Ok(true)
 }
 
-fn assert_not_double_bet(
-    deps: Deps,
-    room_id: u64,
-    living_round: u64,
-    player: &Addr,
-) -> StdResult<bool> {
-    let bet_info_key = bet_info_key(room_id, living_round, &player);
-    let bet_info = bet_info_storage().may_load(deps.storage, bet_info_key)?;
-    if bet_info.is_some() {
-        return Err(StdError::GenericErr {
-            msg: format!("This user already bet on this round for this room"),
-        });
-    }
-
-    Ok(true)
-}
-
+/// Checks exactly `amount` of the room denom is paid, either as native funds
+/// or as cw20 tokens received through the `Receive` hook.
 fn validate_input_amount(
     actual_funds: &[Coin],
+    received: Option<&Cw20CoinVerified>,
     amount: Uint128,
     game_denom: &AssetInfo,
 ) -> Result<(), ContractError> {
+    //every coin held by the contract is booked to a room, so anything else is rejected
     match game_denom {
-        AssetInfo::Token {
-            contract_addr: _contract_address,
-        } => Ok(()),
+        AssetInfo::Token { contract_addr } => {
+            if !actual_funds.is_empty() {
+                return Err(ContractError::UnexpectedFunds {});
+            }
+            let received = match received {
+                Some(received) => received,
+                //nothing to send, e.g. when a bet is lowered
+                None if amount.is_zero() => return Ok(()),
+                None => return Err(ContractError::Cw20SendRequired {}),
+            };
+            if &received.address != contract_addr {
+                return Err(ContractError::IncorrectCw20Token {
+                    provided: received.address.to_string(),
+                    required: contract_addr.to_string(),
+                });
+            }
+            if received.amount != amount {
+                return Err(ContractError::InsufficientFunds {});
+            }
+            Ok(())
+        }
         AssetInfo::NativeToken { denom } => {
-            let actual = get_amount_for_denom(actual_funds, &denom);
+            if received.is_some() || actual_funds.iter().any(|coin| &coin.denom != denom) {
+                return Err(ContractError::UnexpectedFunds {});
+            }
+            let actual = get_amount_for_denom(actual_funds, denom);
             if actual.amount != amount {
                 return Err(ContractError::InsufficientFunds {});
             }
@@ -596,218 +1690,590 @@ fn validate_input_amount(
     }
 }
 
-pub fn get_withdrawal_amount(
+pub fn get_withdrawal_amount(deps: Deps, room_id: u64) -> StdResult<Uint128> {
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    //admin can not withdraw money which is really exceeded than user's limit
+    Ok(bankroll.deposited.saturating_sub(bankroll.locked))
+}
+
+/// Part of the unlocked bankroll of the room owned by the LP shares of the provider
+pub fn get_provider_withdrawal_amount(
     deps: Deps,
-    room_info: &RoomConfig,
-    recipient: &Addr,
+    room_id: u64,
+    provider: &Addr,
 ) -> StdResult<Uint128> {
-    let room_denom = room_info.game_denom.clone();
-    let balance = match room_denom {
-        AssetInfo::Token { contract_addr } => {
-            get_cw20_token_amount(deps, &contract_addr, recipient)?
-        }
-        AssetInfo::NativeToken { denom } => get_native_token_amount(deps, &denom, recipient)?,
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    let shares = LP_SHARES
+        .may_load(
+            deps.storage,
+            lp_shares_key(provider, room_id, bankroll.share_epoch),
+        )?
+        .unwrap_or_default();
+    if shares.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok(get_withdrawal_amount(deps, room_id)?.multiply_ratio(shares, bankroll.total_shares))
+}
+
+/// LP shares worth `amount` of the bankroll, the first deposit gets one share per token
+fn shares_for_amount(amount: Uint128, bankroll: &Bankroll, round_up: bool) -> StdResult<Uint128> {
+    if bankroll.total_shares.is_zero() {
+        return Ok(amount);
+    }
+    if bankroll.deposited.is_zero() {
+        return Err(StdError::generic_err(
+            "The shares of an empty bankroll can not be priced",
+        ));
+    }
+    let shares = amount.full_mul(bankroll.total_shares);
+    let deposited = Uint256::from(bankroll.deposited);
+    let shares = if round_up {
+        (shares + deposited - Uint256::one()) / deposited
+    } else {
+        shares / deposited
     };
+    Ok(Uint128::try_from(shares)?)
+}
 
-    let minimum_limit_for_pool = MINIMUMRESERVE.load(deps.storage)?;
-    //admin can not withdraw money which is really exceeded than user's limit
-    let maximum_withdrawal = balance - minimum_limit_for_pool;
+/// The bankroll of a room still holds the stakes of a round being settled, so its shares can not
+/// be priced until the settlement is done
+fn assert_not_settling(deps: Deps, room_id: u64) -> Result<(), ContractError> {
+    if SETTLEMENT.has(deps.storage, &room_id.to_string()) {
+        return Err(ContractError::RoundSettling {});
+    }
+    Ok(())
+}
+
+/// The winner of the living round may be public once its betting is closed, so the shares can
+/// not be priced until the round is settled, queued withdrawals wait for the settlement anyway
+fn assert_pool_open(
+    deps: Deps,
+    crr_time: u64,
+    room_id: u64,
+    room_info: &RoomConfig,
+) -> Result<(), ContractError> {
+    assert_not_settling(deps, room_id)?;
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    let round_start_time =
+        ROUND_START_SECOND.may_load(deps.storage, round_key(room_id, living_round))?;
+    if let Some(round_start_time) = round_start_time {
+        if crr_time > room_info.betting_close(round_start_time) {
+            return Err(ContractError::PoolLocked {});
+        }
+    }
+    Ok(())
+}
 
-    Ok(maximum_withdrawal)
+/// A zero drand period would divide by zero on the first bet, and no beacon verifies under a
+/// malformed public key
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    if let RandomnessSource::Drand { pubkey, period, .. } = &config.randomness {
+        if *period == 0 || pubkey.len() != 48 {
+            return Err(ContractError::InvalidDrandConfig {});
+        }
+    }
+    //the players can not refund a round the operator still has time to reveal
+    if config.refund_timeout_seconds < config.reveal_timeout_seconds {
+        return Err(ContractError::InvalidTimeouts {});
+    }
+    Ok(())
 }
 
 fn validate_room_id(deps: Deps, room_id: u64) -> StdResult<RoomConfig> {
     let room = ROOMS.may_load(deps.storage, &room_id.to_string())?;
     if room.is_none() {
-        return Err(StdError::generic_err(format!("This room does not exist")));
+        return Err(StdError::generic_err("This room does not exist"));
     }
     Ok(room.unwrap())
 }
 
-fn validate_maximum_reward_exceed(
-    deps: Deps,
-    contract_address: &Addr,
+/// Every payout must return at least the stake, and keep the minimum house edge of the config
+/// on the wheel and with the zero rule of the room
+fn validate_payout_table(config: &Config, room_info: &RoomConfig) -> Result<(), ContractError> {
+    for (bet_type, numbers, payout) in room_info.payout_table.entries(&room_info.wheel) {
+        let expected_return = room_info.expected_return(bet_type, numbers, payout);
+        if payout < Decimal::one() || expected_return + config.min_house_edge > Decimal::one() {
+            return Err(ContractError::InvalidPayout {
+                bet_type: bet_type.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the bets `removed` by `added` in the exposure of a round and checks the bankroll of
+/// the room after this bet (`token_hold_amount`) can pay any pocket
+fn update_exposure(
+    exposure: &mut Exposure,
+    removed: &[BetConfig],
+    added: &[BetConfig],
+    room_info: &RoomConfig,
+    token_hold_amount: Uint128,
+) -> StdResult<()> {
+    let removed_payouts = pocket_payouts(removed, room_info)?;
+    let added_payouts = pocket_payouts(added, room_info)?;
+    for (point, payout) in exposure.pockets.iter_mut().enumerate() {
+        *payout = payout.checked_sub(removed_payouts[point])? + added_payouts[point];
+        if *payout > token_hold_amount {
+            return Err(StdError::GenericErr {
+                msg: format!(
+                    "The room will have {} of tokens after this bet, but if {} is selected as winner, the maximum reward will be {}",
+                    token_hold_amount,
+                    point,
+                    payout
+                ),
+            });
+        }
+    }
+    let removed_stake: Uint128 = removed.iter().map(|bet| bet.amount).sum();
+    let added_stake: Uint128 = added.iter().map(|bet| bet.amount).sum();
+    exposure.total_stake = exposure.total_stake.checked_sub(removed_stake)? + added_stake;
+    Ok(())
+}
+
+/// Adds the bets imprisoned by a zero to the exposure of the next round, they only return
+/// their stake
+fn add_imprisoned_exposure(
+    storage: &mut dyn Storage,
     room_id: u64,
     round_id: u64,
-    bet_info: &Vec<BetConfig>,
-    denom: &AssetInfo,
-) -> StdResult<Uint128> {
-    let room_round_players = query_all_members_one_round_room(deps, room_id, round_id)?;
-    //token or native token amount of this contract
-    let token_hold_amount = match denom {
-        AssetInfo::NativeToken { denom } => get_native_token_amount(deps, denom, contract_address)?,
-        AssetInfo::Token { contract_addr } => {
-            get_cw20_token_amount(deps, contract_addr, contract_address)?
+    room_info: &RoomConfig,
+    imprisoned: &[BetConfig],
+) -> StdResult<()> {
+    let mut exposure = load_round_exposure(storage, room_id, round_id, room_info)?;
+    for bet in imprisoned {
+        let point_ratio_info = get_points_ratio_information(&bet.direction, room_info)?;
+        for point in point_ratio_info.points {
+            if let Some(payout) = exposure.pockets.get_mut(point as usize) {
+                *payout += bet.amount;
+            }
+        }
+        exposure.total_stake += bet.amount;
+    }
+    ROUND_EXPOSURE.save(storage, round_key(room_id, round_id), &exposure)
+}
+
+/// Exposure of the round, with a payout for every pocket of the wheel of the room
+pub fn load_round_exposure(
+    storage: &dyn Storage,
+    room_id: u64,
+    round_id: u64,
+    room_info: &RoomConfig,
+) -> StdResult<Exposure> {
+    let mut exposure = ROUND_EXPOSURE
+        .may_load(storage, round_key(room_id, round_id))?
+        .unwrap_or_default();
+    exposure
+        .pockets
+        .resize(room_info.wheel.pockets() as usize, Uint128::zero());
+    Ok(exposure)
+}
+
+/// pockets of the european wheel in the order they are laid out on the wheel
+const EUROPEAN_WHEEL_ORDER: [u32; 37] = [
+    0, 32, 15, 19, 4, 21, 2, 25, 17, 34, 6, 27, 13, 36, 11, 30, 8, 23, 10, 5, 24, 16, 33, 1, 20,
+    14, 31, 9, 22, 18, 29, 7, 28, 12, 35, 3, 26,
+];
+/// pockets of the american wheel in the order they are laid out on the wheel, 37 is 00
+const AMERICAN_WHEEL_ORDER: [u32; 38] = [
+    0, 28, 9, 26, 30, 11, 7, 20, 32, 17, 5, 22, 34, 15, 3, 24, 36, 13, 1, 37, 27, 10, 25, 29, 12,
+    8, 19, 31, 18, 6, 21, 33, 16, 4, 23, 35, 14, 2,
+];
+
+/// Chips an announced bet is placed with, and the units of the stake each of them takes,
+/// none for a bet placed with a single chip
+fn call_bet_chips(
+    direction: &Direction,
+    wheel: &Wheel,
+) -> StdResult<Option<Vec<(Direction, u128)>>> {
+    let split = |first, second| (Direction::Split { first, second }, 1);
+    match direction {
+        Direction::CallBet { kind } => {
+            if *wheel != Wheel::European {
+                return Err(StdError::GenericErr {
+                    msg: "Call bets are only available on the european wheel".to_string(),
+                });
+            }
+            let chips = match kind {
+                CallBetKind::VoisinsDuZero => vec![
+                    (Direction::Trio { id: 2 }, 2),
+                    split(4, 7),
+                    split(12, 15),
+                    split(18, 21),
+                    split(19, 22),
+                    (Direction::Corner { id: 25 }, 2),
+                    split(32, 35),
+                ],
+                CallBetKind::Tiers => vec![
+                    split(5, 8),
+                    split(10, 11),
+                    split(13, 16),
+                    split(23, 24),
+                    split(27, 30),
+                    split(33, 36),
+                ],
+                CallBetKind::Orphelins => vec![
+                    (Direction::Single { id: 1 }, 1),
+                    split(6, 9),
+                    split(14, 17),
+                    split(17, 20),
+                    split(31, 34),
+                ],
+            };
+            Ok(Some(chips))
+        }
+        Direction::Neighbours { center, width } => {
+            let order: &[u32] = match wheel {
+                Wheel::European => &EUROPEAN_WHEEL_ORDER,
+                Wheel::American => &AMERICAN_WHEEL_ORDER,
+            };
+            let len = order.len();
+            let position = order.iter().position(|pocket| pocket == center);
+            let position = match position {
+                Some(position) if *width >= 1 && (*width as usize) * 2 < len => position,
+                _ => {
+                    return Err(StdError::GenericErr {
+                        msg: format!("{} neighbours of {} are not on the wheel", width, center),
+                    })
+                }
+            };
+            let width = *width as usize;
+            let chips = (0..=width * 2)
+                .map(|i| {
+                    let id = order[(position + len + i - width) % len];
+                    (Direction::Single { id }, 1)
+                })
+                .collect();
+            Ok(Some(chips))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Every chip of the bet with its share of the stake, an announced bet is spread evenly
+/// over its chips and each of them is paid by its own ratio
+pub fn get_bet_chips(
+    bet: &BetConfig,
+    room_info: &RoomConfig,
+) -> StdResult<Vec<(PointRatioInfo, Uint128)>> {
+    let chips = match call_bet_chips(&bet.direction, &room_info.wheel)? {
+        Some(chips) => chips,
+        None => {
+            return Ok(vec![(
+                get_points_ratio_information(&bet.direction, room_info)?,
+                bet.amount,
+            )])
         }
     };
+    let units: u128 = chips.iter().map(|(_, units)| units).sum();
+    let unit = bet.amount.u128() / units;
+    if unit * units != bet.amount.u128() {
+        return Err(StdError::GenericErr {
+            msg: format!(
+                "The stake of this bet must be a multiple of {} to be spread over its chips",
+                units
+            ),
+        });
+    }
+    chips
+        .into_iter()
+        .map(|(direction, units)| {
+            Ok((
+                get_points_ratio_information(&direction, room_info)?,
+                Uint128::new(unit * units),
+            ))
+        })
+        .collect()
+}
 
-    //this parameter must be needed so that the admin can not exceed for the maximum reward for users.
-    let mut minimum_reserve_limit = MINIMUMRESERVE.load(deps.storage)?;
-
-    //we will check for each point
-    for i in 0..38 {
-        let point = i as u32;
-        //this number will be the output of reward at the poing of point(above number).
-        let mut maximum_amount_test = Uint128::zero();
-        //first get all player lists for one room and one round_id, users can not bet twice on one round, one room
-        //every user can do several bets with vector type for one transaction
-        //get available points for each bet config and ratio
-        //check if this includes the point and if it includes we will increase the amount
-        //This step is for prev users for this room and id
-
-        for player_bet_info in &room_round_players.bets_info {
-            for bet in &player_bet_info.bet_info {
-                let point_ratio_info = get_points_ratio_information(&bet.direction)?;
-                let index = point_ratio_info.points.iter().position(|&x| x == point);
-                if index.is_some() {
-                    maximum_amount_test = maximum_amount_test
-                        + bet.amount * Uint128::new(point_ratio_info.ratio as u128);
+/// Payout of the bets on each pocket of the wheel of the room, indexed by pocket
+pub fn pocket_payouts(bets: &[BetConfig], room_info: &RoomConfig) -> StdResult<Vec<Uint128>> {
+    let mut payouts = vec![Uint128::zero(); room_info.wheel.pockets() as usize];
+    for bet in bets {
+        for (point_ratio_info, amount) in get_bet_chips(bet, room_info)? {
+            for point in point_ratio_info.points {
+                if let Some(payout) = payouts.get_mut(point as usize) {
+                    *payout += amount * point_ratio_info.ratio;
                 }
             }
         }
-
-        //add the additional info for this user(new comer) and this is the step for the current user join
-        for bet in bet_info {
-            let point_ratio_info = get_points_ratio_information(&bet.direction)?;
-            let index = point_ratio_info.points.iter().position(|&x| x == point);
-            if index.is_some() {
-                maximum_amount_test =
-                    maximum_amount_test + bet.amount * Uint128::new(point_ratio_info.ratio as u128);
+        //la partage returns half of the even money stakes on a zero
+        if room_info.zero_rule == ZeroRule::LaPartage && is_even_money(&bet.direction) {
+            let half = bet.amount.multiply_ratio(1u128, 2u128);
+            for (point, payout) in payouts.iter_mut().enumerate() {
+                if room_info.wheel.is_zero(point as u32) {
+                    *payout += half;
+                }
             }
         }
+    }
+    Ok(payouts)
+}
 
-        if maximum_amount_test > minimum_reserve_limit {
-            minimum_reserve_limit = maximum_amount_test;
+/// What each pocket credits the player for `bets` when it wins, the platform fee taken
+pub fn pocket_winnings(
+    bets: &[BetConfig],
+    room_info: &RoomConfig,
+    platform_fee: Decimal,
+) -> StdResult<Vec<Uint128>> {
+    let mut winnings = vec![Uint128::zero(); room_info.wheel.pockets() as usize];
+    for bet in bets {
+        for (point_ratio_info, amount) in get_bet_chips(bet, room_info)? {
+            for point in point_ratio_info.points {
+                if let Some(winning) = winnings.get_mut(point as usize) {
+                    *winning += amount * point_ratio_info.ratio * (Decimal::one() - platform_fee);
+                }
+            }
         }
+    }
+    Ok(winnings)
+}
 
-        if maximum_amount_test > token_hold_amount {
-            return Err(StdError::GenericErr {
-                msg: format!(
-                    "The contract will have {} of tokens after this bet, but if {} is selected as winner, the maximum reward will be {}",
-                    token_hold_amount,
-                    point,
-                    maximum_amount_test
-                ),
-            });
+/// Largest stake on `direction` the room takes now, within its max bet and so that the bet pays
+/// at most the risk fraction of the free bankroll on any pocket, the whole free bankroll when the
+/// room has no risk fraction
+pub fn get_max_allowed_bet(
+    deps: Deps,
+    room_id: u64,
+    room_info: &RoomConfig,
+    direction: &Direction,
+) -> StdResult<Uint128> {
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    let free = bankroll.deposited.saturating_sub(bankroll.locked);
+    let max_payout = free * room_info.risk_fraction.unwrap_or_else(Decimal::one);
+
+    //the stake of a call bet is spread over its chips, so it goes by multiples of their units
+    let units = match call_bet_chips(direction, &room_info.wheel)? {
+        Some(chips) => chips.iter().map(|(_, units)| units).sum(),
+        None => 1u128,
+    };
+    let unit_bet = BetConfig {
+        direction: direction.clone(),
+        amount: Uint128::new(units),
+    };
+    //what each pocket pays per unit of stake, unrounded so fractional ratios keep their part
+    let mut unit_rates = vec![Decimal::zero(); room_info.wheel.pockets() as usize];
+    for (point_ratio_info, amount) in get_bet_chips(&unit_bet, room_info)? {
+        let chip_rate = Decimal::from_ratio(amount, 1u128) * point_ratio_info.ratio;
+        for point in point_ratio_info.points {
+            if let Some(rate) = unit_rates.get_mut(point as usize) {
+                *rate += chip_rate;
+            }
+        }
+    }
+    if room_info.zero_rule == ZeroRule::LaPartage && is_even_money(direction) {
+        let half = Decimal::from_ratio(units, 2u128);
+        for (point, rate) in unit_rates.iter_mut().enumerate() {
+            if room_info.wheel.is_zero(point as u32) {
+                *rate += half;
+            }
         }
     }
+    let unit_rate = unit_rates.into_iter().max().unwrap_or_default();
+    if unit_rate.is_zero() {
+        return Ok(room_info.max_bet);
+    }
+    let max_units = max_payout.multiply_ratio(Decimal::one().atomics(), unit_rate.atomics());
+    Ok(room_info.max_bet.min(max_units * Uint128::new(units)))
+}
 
-    Ok(minimum_reserve_limit)
+/// Bets paying 1 to 1, the zero rule of the room applies to them
+fn is_even_money(direction: &Direction) -> bool {
+    matches!(
+        direction,
+        Direction::Odd
+            | Direction::Even
+            | Direction::FirstHalf
+            | Direction::SecondHalf
+            | Direction::Blue
+            | Direction::Black
+    )
 }
 
-pub fn get_points_ratio_information(direction: &Direction) -> StdResult<PointRatioInfo> {
+pub fn get_points_ratio_information(
+    direction: &Direction,
+    room_info: &RoomConfig,
+) -> StdResult<PointRatioInfo> {
+    let wheel = &room_info.wheel;
+    let payouts = &room_info.payout_table;
     match direction {
         Direction::Odd => Ok(PointRatioInfo {
             points: vec![
                 1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31, 33, 35,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
         Direction::Even => Ok(PointRatioInfo {
             points: vec![
                 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30, 32, 34, 36,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
         Direction::FirstHalf => Ok(PointRatioInfo {
             points: vec![
                 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
         Direction::SecondHalf => Ok(PointRatioInfo {
             points: vec![
                 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
         Direction::Blue => Ok(PointRatioInfo {
             points: vec![
                 1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
         Direction::Black => Ok(PointRatioInfo {
             points: vec![
                 2, 4, 6, 8, 10, 11, 13, 15, 17, 20, 22, 24, 26, 28, 29, 31, 33, 35,
             ],
-            ratio: 2,
+            ratio: payouts.even_money,
         }),
-        Direction::Row { id } => {
+        Direction::Column { id } => {
             if *id < 1 || *id > 3 {
                 return Err(StdError::GenericErr {
-                    msg: format!("The row select parameter must be one of 1,2 and 3"),
+                    msg: "The column select parameter must be one of 1,2 and 3".to_string(),
                 });
             }
-            let mut row_ids: Vec<u32> = Vec::new();
+            let mut column_ids: Vec<u32> = Vec::new();
             for i in 0..12 {
-                row_ids.push(id + i * 3);
-            }
-            Ok(PointRatioInfo {
-                points: row_ids,
-                ratio: 3,
-            })
-        }
-        Direction::Column { id } => {
-            if *id < 1 || *id > 12 {
-                return Err(StdError::GenericErr {
-                    msg: format!("The row select parameter must be in tje range pf 1 to 12 "),
-                });
+                column_ids.push(id + i * 3);
             }
-            let id_start = (id - 1) * 3 + 1;
             Ok(PointRatioInfo {
-                points: vec![id_start, id_start + 1, id_start + 2],
-                ratio: 12,
+                points: column_ids,
+                ratio: payouts.column,
             })
         }
         Direction::FirstOfThird => Ok(PointRatioInfo {
             points: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
-            ratio: 3,
+            ratio: payouts.dozen,
         }),
         Direction::SecondOfThird => Ok(PointRatioInfo {
             points: vec![13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24],
-            ratio: 3,
+            ratio: payouts.dozen,
         }),
         Direction::ThirdOfThird => Ok(PointRatioInfo {
             points: vec![25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36],
-            ratio: 3,
+            ratio: payouts.dozen,
         }),
         Direction::Single { id } => {
-            if *id > 37 {
+            if *id >= wheel.pockets() {
                 return Err(StdError::GenericErr {
-                    msg: format!("The row select parameter must be in the range of 0 to 37 "),
+                    msg: format!(
+                        "The single select parameter must be in the range of 0 to {}",
+                        wheel.pockets() - 1
+                    ),
                 });
             }
             Ok(PointRatioInfo {
                 points: vec![*id],
-                ratio: 36,
+                ratio: payouts.single,
+            })
+        }
+        Direction::Split { first, second } => {
+            let (low, high) = (*first.min(second), *first.max(second));
+            //numbers of the same street side by side, or one above the other
+            let adjacent_numbers =
+                low >= 1 && high <= 36 && ((high == low + 1 && low % 3 != 0) || high == low + 3);
+            let zero_split = match wheel {
+                Wheel::European => matches!((low, high), (0, 1) | (0, 2) | (0, 3)),
+                Wheel::American => matches!(
+                    (low, high),
+                    (0, 1) | (0, 2) | (0, DOUBLE_ZERO) | (2, DOUBLE_ZERO) | (3, DOUBLE_ZERO)
+                ),
+            };
+            if !adjacent_numbers && !zero_split {
+                return Err(StdError::GenericErr {
+                    msg: format!("{} and {} are not adjacent on the table", low, high),
+                });
+            }
+            Ok(PointRatioInfo {
+                points: vec![low, high],
+                ratio: payouts.split,
+            })
+        }
+        Direction::Street { id } => {
+            if *id < 1 || *id > 12 {
+                return Err(StdError::GenericErr {
+                    msg: "The street select parameter must be in the range of 1 to 12".to_string(),
+                });
+            }
+            let id_start = (id - 1) * 3 + 1;
+            Ok(PointRatioInfo {
+                points: vec![id_start, id_start + 1, id_start + 2],
+                ratio: payouts.street,
+            })
+        }
+        Direction::Corner { id } => {
+            //the corner needs a number on its right and a line below it
+            if *id < 1 || *id > 32 || id % 3 == 0 {
+                return Err(StdError::GenericErr {
+                    msg: format!("{} is not the lowest number of a corner", id),
+                });
+            }
+            Ok(PointRatioInfo {
+                points: vec![*id, id + 1, id + 3, id + 4],
+                ratio: payouts.corner,
+            })
+        }
+        Direction::SixLine { id } => {
+            if *id < 1 || *id > 11 {
+                return Err(StdError::GenericErr {
+                    msg: "The six line select parameter must be in the range of 1 to 11"
+                        .to_string(),
+                });
+            }
+            let id_start = (id - 1) * 3 + 1;
+            Ok(PointRatioInfo {
+                points: (id_start..id_start + 6).collect(),
+                ratio: payouts.six_line,
             })
         }
+        Direction::Trio { id } => match id {
+            1 => Ok(PointRatioInfo {
+                points: vec![0, 1, 2],
+                ratio: payouts.trio,
+            }),
+            2 => Ok(PointRatioInfo {
+                points: match wheel {
+                    Wheel::European => vec![0, 2, 3],
+                    Wheel::American => vec![DOUBLE_ZERO, 2, 3],
+                },
+                ratio: payouts.trio,
+            }),
+            _ => Err(StdError::GenericErr {
+                msg: "The trio select parameter must be one of 1 and 2".to_string(),
+            }),
+        },
+        Direction::Basket => match wheel {
+            Wheel::European => Err(StdError::GenericErr {
+                msg: "The basket is only available on the american wheel".to_string(),
+            }),
+            Wheel::American => Ok(PointRatioInfo {
+                points: vec![0, DOUBLE_ZERO, 2],
+                ratio: payouts.basket,
+            }),
+        },
+        Direction::CallBet { .. } | Direction::Neighbours { .. } => Err(StdError::GenericErr {
+            msg: "Announced bets are placed with several chips, see get_bet_chips".to_string(),
+        }),
+        Direction::TopLine => match wheel {
+            Wheel::European => Ok(PointRatioInfo {
+                points: vec![0, 1, 2, 3],
+                ratio: payouts.top_line,
+            }),
+            Wheel::American => Ok(PointRatioInfo {
+                points: vec![0, DOUBLE_ZERO, 1, 2, 3],
+                ratio: payouts.top_line,
+            }),
+        },
     }
 }
 
-pub fn new_entropy(info: &MessageInfo, env: &Env, seed: &[u8], entropy: &[u8]) -> [u8; 32] {
-    // 16 here represents the lengths in bytes of the block height and time.
-    let entropy_len = 16 + info.sender.to_string().len() + entropy.len();
-    let mut rng_entropy = Vec::with_capacity(entropy_len);
-    rng_entropy.extend_from_slice(&env.block.height.to_be_bytes());
-    rng_entropy.extend_from_slice(&env.block.time.nanos().to_be_bytes());
-    rng_entropy.extend_from_slice(&info.sender.as_bytes());
-    rng_entropy.extend_from_slice(entropy);
-
-    let mut rng = Prng::new(seed, &rng_entropy);
-
-    rng.rand_bytes()
-}
-
-pub fn rand_generator(info: &MessageInfo, env: &Env) -> u32 {
-    let prng_seed: Vec<u8> = sha_256(base64::encode("entropy").as_bytes()).to_vec();
-    let random_seed = new_entropy(&info, &env, prng_seed.as_ref(), prng_seed.as_ref());
-    let mut rng = ChaChaRng::from_seed(random_seed);
-    let rand_num = rng.next_u32();
-    rand_num % 38
-}
-
 pub fn query_all_members_one_round_room(
     deps: Deps,
     room_id: u64,
@@ -855,28 +2321,6 @@ pub fn get_cw20_transfer_msg(
     Ok(cw20_transfer_msg)
 }
 
-pub fn get_cw20_transfer_from_msg(
-    token_addr: &Addr,
-    owner: &Addr,
-    recipient: &Addr,
-    amount: Uint128,
-) -> StdResult<CosmosMsg> {
-    let transfer_cw20_msg = Cw20ExecuteMsg::TransferFrom {
-        owner: owner.into(),
-        recipient: recipient.into(),
-        amount,
-    };
-
-    let exec_cw20_transfer_msg = WasmMsg::Execute {
-        contract_addr: token_addr.into(),
-        msg: to_binary(&transfer_cw20_msg)?,
-        funds: vec![],
-    };
-
-    let cw20_transfer_msg: CosmosMsg = exec_cw20_transfer_msg.into();
-    Ok(cw20_transfer_msg)
-}
-
 pub fn get_cw20_burn_from_msg(
     token_addr: &Addr,
     owner: &Addr,
@@ -912,18 +2356,3 @@ pub fn get_bank_transfer_to_msg(
     let transfer_bank_cosmos_msg: CosmosMsg = transfer_bank_msg.into();
     Ok(transfer_bank_cosmos_msg)
 }
-
-fn get_native_token_amount(deps: Deps, denom: &String, recipient: &Addr) -> StdResult<Uint128> {
-    let balance = deps.querier.query_balance(recipient, denom)?;
-    Ok(balance.amount)
-}
-
-fn get_cw20_token_amount(deps: Deps, contract_addr: &Addr, recipient: &Addr) -> StdResult<Uint128> {
-    let balance: BalanceResponse = deps.querier.query_wasm_smart(
-        contract_addr.to_string(),
-        &(Cw20QueryMsg::Balance {
-            address: recipient.to_string(),
-        }),
-    )?;
-    Ok(balance.balance)
-}
@@ -1,3 +1,4 @@
+pub mod drand;
 pub mod error;
 pub mod execute;
 pub mod msg;
@@ -1,7 +1,11 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, HexBinary, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
 
-use crate::state::{BetInfo, Config, RoomConfig, RoomInfo, State};
+use crate::state::{
+    Bankroll, BetInfo, Config, Exposure, PayoutTable, RoomConfig, RoomInfo, RoundProof, Settlement,
+    State, Wheel,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -16,15 +20,83 @@ pub enum ExecuteMsg {
     AddRoom {
         room_info: RoomConfig,
     },
+    /// the chips are added to the bets of the sender in the living round, or replace them
+    /// when `replace` is set, the replaced stake is then refunded unless the bets are locked
     Bet {
         room_id: u64,
         bet_info: Vec<BetConfig>,
+        replace: Option<bool>,
     },
-    CloseRound {},
+    /// replace the bets of the sender in the living round, only the difference of the stakes
+    /// is sent or refunded, refused in the last `bet_lock_seconds` of the betting time
+    ModifyBet {
+        room_id: u64,
+        bet_info: Vec<BetConfig>,
+    },
+    /// remove the bets of the sender in the living round and refund their stake,
+    /// refused in the last `bet_lock_seconds` of the betting time
+    CancelBet {
+        room_id: u64,
+    },
+    /// cw20 tokens sent with a `ReceiveMsg` to bet in or deposit to a room playing with this token
+    Receive(Cw20ReceiveMsg),
+    /// operator commits sha256(secret) for the living round before bets are accepted
+    CommitRound {
+        room_id: u64,
+        commitment: Binary,
+    },
+    /// reveal the committed secret (or submit the drand beacon) to draw the winner,
+    /// or pass none once the reveal timeout is over to refund all bets of the round
+    CloseRound {
+        room_id: u64,
+        secret: Option<Binary>,
+        beacon: Option<DrandBeacon>,
+    },
+    /// callback of the randomness proxy for a round requested on close
+    ReceiveRandomness {
+        callback: RandomnessCallback,
+    },
+    /// any player of the living round refunds all its bets once the refund timeout is over,
+    /// unless its drand beacon is already due
+    RefundRound {
+        room_id: u64,
+        round_id: u64,
+    },
+    /// credit the next `limit` bets of a closed round, anyone can call it until the round is settled
+    SettleRound {
+        room_id: u64,
+        round_id: u64,
+        limit: Option<u32>,
+    },
+    /// transfer the platform fees owed by a room to the distributor
+    CollectFees {
+        room_id: u64,
+    },
+    /// pay out the winnings and refunds credited to the sender in one room
+    Claim {
+        room_id: u64,
+    },
+    /// pay out the winnings and refunds credited to the sender in every room
+    ClaimAll {},
+    /// withdraw liquidity by burning LP shares, capped by the sender's part of the unlocked bankroll,
+    /// refused from the betting close until the round is settled
     WithdrawFromPool {
         room_id: u64,
         amount: Uint128,
     },
+    /// escrow LP shares to be withdrawn at the next settlements, as soon as the exposure of the
+    /// living round is released, the withdrawn amount is credited like a winning
+    QueueWithdrawal {
+        room_id: u64,
+        shares: Uint128,
+    },
+    /// the provider takes back the shares of a queued withdrawal that is not paid yet
+    CancelWithdrawal {
+        room_id: u64,
+        withdrawal_id: u64,
+    },
+    /// anyone provides liquidity to the room and gets LP shares of its bankroll, refused from the
+    /// betting close until the round is settled
     Deposit {
         room_id: u64,
         amount: Uint128,
@@ -38,6 +110,28 @@ pub enum ExecuteMsg {
         room_id: u64,
         max_bet: Uint128,
         min_bet: Uint128,
+        risk_fraction: Option<Decimal>,
+    },
+    /// room owner changes the payouts, only while the living round has no bets
+    UpdatePayoutTable {
+        room_id: u64,
+        payout_table: PayoutTable,
+    },
+}
+
+#[cw_serde]
+pub enum ReceiveMsg {
+    Bet {
+        room_id: u64,
+        bet_info: Vec<BetConfig>,
+        replace: Option<bool>,
+    },
+    ModifyBet {
+        room_id: u64,
+        bet_info: Vec<BetConfig>,
+    },
+    Deposit {
+        room_id: u64,
     },
 }
 
@@ -45,7 +139,9 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Config {},
     State {},
-    AllState {},
+    AllState {
+        room_id: u64,
+    },
     GetRoom {
         room_id: u64,
     },
@@ -53,6 +149,11 @@ pub enum QueryMsg {
         start_after: Option<u64>,
         limit: Option<u32>,
     },
+    /// living rounds a keeper can close now, `start_after` and `limit` page through the rooms
+    CloseableRounds {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     GetPlayersForOneRoundOneRoom {
         room_id: u64,
         round_id: u64,
@@ -68,24 +169,78 @@ pub enum QueryMsg {
     GetMaximumWithdrawlFromRoom {
         room_id: u64,
     },
+    GetLiquidity {
+        room_id: u64,
+        provider: Addr,
+    },
+    GetWithdrawalQueue {
+        room_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     GetWinnerRound {
+        room_id: u64,
         round_id: u64,
     },
     GetWinnerLists {
+        room_id: u64,
         start_after: Option<u64>,
         limit: Option<u32>,
     },
     GetGameInfoForRound {
+        room_id: u64,
         round_id: u64,
-        start_after: Option<RoundOffset>,
+        start_after: Option<String>,
         limit: Option<u32>,
     },
+    RoundProof {
+        room_id: u64,
+        round_id: u64,
+    },
+    GetPendingWinnings {
+        player: Addr,
+    },
+    GetBankroll {
+        room_id: u64,
+    },
+    GetRoomOdds {
+        room_id: u64,
+    },
+    /// payout owed on each pocket for the bets of the round, for risk dashboards
+    GetRoundExposure {
+        room_id: u64,
+        round_id: u64,
+    },
+    /// largest stake on `direction` the room takes now, UIs grey out the bets above it
+    MaxAllowedBet {
+        room_id: u64,
+        direction: Direction,
+    },
+    /// runs the checks of `Bet` for these new chips of `player`, but the funds sent with it
+    SimulateBet {
+        room_id: u64,
+        player: Addr,
+        bet_info: Vec<BetConfig>,
+    },
 }
 
 #[cw_serde]
-pub struct RoundOffset {
-    pub room_id: u64,
-    pub player: Addr,
+pub struct DrandBeacon {
+    pub round: u64,
+    pub signature: Binary,
+}
+
+#[cw_serde]
+pub struct RandomnessCallback {
+    pub job_id: String,
+    pub published: Timestamp,
+    pub randomness: HexBinary,
+}
+
+/// Request interface of the randomness proxy contract
+#[cw_serde]
+pub enum RandomnessProxyExecuteMsg {
+    GetNextRandomness { job_id: String },
 }
 
 #[cw_serde]
@@ -106,7 +261,14 @@ pub struct AllStateResponse {
     pub state: State,
     pub config: Config,
     pub crr_time: u64,
+    pub room_id: u64,
+    pub living_round: u64,
     pub round_start_second: u64,
+    /// last second bets are accepted in the living round
+    pub betting_close: Option<u64>,
+    /// first second the living round can be closed
+    pub earliest_close: Option<u64>,
+    pub settlement: Option<Settlement>,
 }
 
 #[cw_serde]
@@ -119,6 +281,24 @@ pub struct RoomsInfoResponse {
     pub rooms: Vec<RoomInfo>,
 }
 
+#[cw_serde]
+pub struct CloseableRound {
+    pub room_id: String,
+    pub round_id: String,
+    pub closeable_since: u64,
+    /// the drand round whose beacon closes the round, when drand is the randomness source
+    pub drand_round: Option<u64>,
+    /// closing the round refunds its bets, no keeper reward is paid
+    pub void: bool,
+    /// share of the platform fee of the round credited to the closer once it is settled
+    pub keeper_reward: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct CloseableRoundsResponse {
+    pub rounds: Vec<CloseableRound>,
+}
+
 #[cw_serde]
 pub struct BetsInfoResponse {
     pub bets_info: Vec<BetInfo>,
@@ -129,9 +309,40 @@ pub struct WithdrawResponse {
     pub amount: Uint128,
 }
 
+#[cw_serde]
+pub struct LiquidityResponse {
+    pub room_id: String,
+    pub shares: Uint128,
+    pub total_shares: Uint128,
+    /// part of the bankroll owned by the shares
+    pub value: Uint128,
+    /// part of the unlocked bankroll the provider can withdraw now
+    pub withdrawable: Uint128,
+}
+
+#[cw_serde]
+pub struct QueuedWithdrawal {
+    pub id: u64,
+    pub provider: Addr,
+    pub shares: Uint128,
+    pub requested_at: u64,
+    /// part of the bankroll owned by the escrowed shares now
+    pub value: Uint128,
+}
+
+#[cw_serde]
+pub struct WithdrawalQueueResponse {
+    pub room_id: String,
+    pub withdrawals: Vec<QueuedWithdrawal>,
+}
+
 #[cw_serde]
 pub struct Winner {
-    pub winner: u32,
+    /// none until the round is drawn
+    pub winner: Option<u32>,
+    /// the round was closed without a winner and its bets refunded
+    pub void: bool,
+    pub room_id: String,
     pub round_id: String,
 }
 
@@ -145,6 +356,81 @@ pub struct WinnerListResponse {
     pub winner_list: Vec<Winner>,
 }
 
+#[cw_serde]
+pub struct BankrollResponse {
+    pub room_id: String,
+    pub bankroll: Bankroll,
+}
+
+#[cw_serde]
+pub struct BetOdds {
+    pub bet_type: String,
+    pub numbers: u32,
+    pub payout: Decimal,
+    /// share of the stakes the room keeps on average
+    pub house_edge: Decimal,
+}
+
+#[cw_serde]
+pub struct RoomOddsResponse {
+    pub room_id: String,
+    pub wheel: Wheel,
+    pub odds: Vec<BetOdds>,
+}
+
+#[cw_serde]
+pub struct RoundExposureResponse {
+    pub room_id: String,
+    pub round_id: String,
+    pub exposure: Exposure,
+    /// part of the bankroll locked for the round
+    pub reserve: Uint128,
+}
+
+#[cw_serde]
+pub struct MaxAllowedBetResponse {
+    pub room_id: String,
+    pub direction: Direction,
+    pub max_bet: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateBetResponse {
+    pub room_id: String,
+    pub player: String,
+    /// whether `Bet` would take the chips now
+    pub success: bool,
+    /// why `Bet` would fail
+    pub error: Option<String>,
+    pub total_stake: Uint128,
+    /// what the chips credit the player on each pocket they win, the platform fee taken
+    pub payouts: Vec<PocketPayout>,
+}
+
+#[cw_serde]
+pub struct PocketPayout {
+    pub pocket: u32,
+    pub payout: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingWinning {
+    pub room_id: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingWinningsResponse {
+    pub winnings: Vec<PendingWinning>,
+}
+
+#[cw_serde]
+pub struct RoundProofResponse {
+    pub room_id: String,
+    pub round_id: String,
+    pub proof: RoundProof,
+}
+
 #[cw_serde]
 pub enum Direction {
     Odd,
@@ -153,12 +439,61 @@ pub enum Direction {
     SecondHalf,
     Blue,
     Black,
-    Row { id: u32 },
-    Column { id: u32 },
+    /// one of the 3 columns of 12 numbers, 1 is the column starting with 1
+    Column {
+        id: u32,
+    },
     FirstOfThird,
     SecondOfThird,
     ThirdOfThird,
-    Single { id: u32 },
+    /// 37 is the double zero pocket of the american wheel
+    Single {
+        id: u32,
+    },
+    /// 2 adjacent numbers of the table
+    Split {
+        first: u32,
+        second: u32,
+    },
+    /// one of the 12 lines of 3 numbers, 1 is the line of 1, 2 and 3
+    Street {
+        id: u32,
+    },
+    /// 4 numbers sharing a corner, `id` is the lowest of them
+    Corner {
+        id: u32,
+    },
+    /// the 2 adjacent streets `id` and `id + 1`
+    SixLine {
+        id: u32,
+    },
+    /// 1 is 0, 1 and 2, 2 is 0 (00 on the american wheel), 2 and 3
+    Trio {
+        id: u32,
+    },
+    /// 0, 00 and 2, only on the american wheel
+    Basket,
+    /// 0, 1, 2 and 3, with 00 on the american wheel
+    TopLine,
+    /// announced bet of the european wheel, the stake is spread over its chips
+    CallBet {
+        kind: CallBetKind,
+    },
+    /// straight chips on `center` and the `width` pockets on each side of it on the wheel
+    Neighbours {
+        center: u32,
+        width: u32,
+    },
+}
+
+#[cw_serde]
+pub enum CallBetKind {
+    /// 9 chips around zero: 0/2/3 (2 chips), 4/7, 12/15, 18/21, 19/22, 25/26/28/29 (2 chips), 32/35
+    VoisinsDuZero,
+    /// 6 splits opposite to zero: 5/8, 10/11, 13/16, 23/24, 27/30, 33/36
+    Tiers,
+    /// 5 chips on the rest of the wheel: 1, 6/9, 14/17, 17/20, 31/34
+    Orphelins,
 }
 
 #[cw_serde]
@@ -170,25 +505,33 @@ pub struct BetConfig {
 #[cw_serde]
 pub struct PointRatioInfo {
     pub points: Vec<u32>,
-    pub ratio: u32,
+    pub ratio: Decimal,
 }
 
-impl Into<std::string::String> for Direction {
-    fn into(self) -> std::string::String {
+impl From<Direction> for std::string::String {
+    fn from(direction: Direction) -> std::string::String {
         // Convert the direction to a string and return it
-        match self {
+        match direction {
             Direction::Odd => "odd".to_string(),
             Direction::Even => "even".to_string(),
             Direction::FirstHalf => "first_half".to_string(),
             Direction::SecondHalf => "second_half".to_string(),
             Direction::Blue => "red".to_string(),
             Direction::Black => "black".to_string(),
-            Direction::Row { id: _ } => "row".to_string(),
             Direction::Column { id: _ } => "column".to_string(),
             Direction::FirstOfThird => "first_of_third".to_string(),
             Direction::SecondOfThird => "second of third".to_string(),
             Direction::ThirdOfThird => "third of third".to_string(),
             Direction::Single { id: _ } => "single".to_string(),
+            Direction::Split { .. } => "split".to_string(),
+            Direction::Street { id: _ } => "street".to_string(),
+            Direction::Corner { id: _ } => "corner".to_string(),
+            Direction::SixLine { id: _ } => "six_line".to_string(),
+            Direction::Trio { id: _ } => "trio".to_string(),
+            Direction::Basket => "basket".to_string(),
+            Direction::TopLine => "top_line".to_string(),
+            Direction::CallBet { kind: _ } => "call_bet".to_string(),
+            Direction::Neighbours { .. } => "neighbours".to_string(),
         }
     }
 }
@@ -1,16 +1,24 @@
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Addr, Binary, Deps, Env, Order, StdResult, Uint128};
+use cosmwasm_std::{to_binary, Addr, Binary, Decimal, Deps, Env, Order, StdResult, Uint128};
 use cw_storage_plus::Bound;
 
-use crate::execute::get_withdrawal_amount;
+use crate::drand::drand_round_time;
+use crate::execute::{
+    get_max_allowed_bet, get_provider_withdrawal_amount, get_withdrawal_amount,
+    load_round_exposure, pocket_winnings, simulate_bet,
+};
 use crate::msg::{
-    AllStateResponse, BetsInfoResponse, ConfigResponse, QueryMsg, RoomInfoResponse,
-    RoomsInfoResponse, RoundOffset, StateResponse, Winner, WinnerListResponse, WinnerResponse,
-    WithdrawResponse,
+    AllStateResponse, BankrollResponse, BetConfig, BetOdds, BetsInfoResponse, CloseableRound,
+    CloseableRoundsResponse, ConfigResponse, Direction, LiquidityResponse, MaxAllowedBetResponse,
+    PendingWinning, PendingWinningsResponse, PocketPayout, QueryMsg, QueuedWithdrawal,
+    RoomInfoResponse, RoomOddsResponse, RoomsInfoResponse, RoundExposureResponse,
+    RoundProofResponse, SimulateBetResponse, StateResponse, Winner, WinnerListResponse,
+    WinnerResponse, WithdrawResponse, WithdrawalQueueResponse,
 };
 use crate::state::{
-    bet_info_key, bet_info_storage, RoomInfo, CONFIG, ROOMS, ROUND_START_SECOND, STATE,
-    WINNERNUMBER,
+    bet_info_key, bet_info_storage, lp_shares_key, round_key, RandomnessSource, RoomInfo, BANKROLL,
+    CLAIMABLE, CONFIG, LIVING_ROUND, LP_SHARES, ROOMS, ROUND_DRAND, ROUND_DRAWING, ROUND_PROOF,
+    ROUND_START_SECOND, SETTLEMENT, STATE, VOID_ROUNDS, WINNERNUMBER, WITHDRAWAL_QUEUE,
 };
 
 const DEFAULT_QUERY_LIMIT: u32 = 10;
@@ -22,28 +30,27 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State {} => to_binary(&query_state(deps)?),
         QueryMsg::GetRoom { room_id } => to_binary(&query_room_info(deps, room_id)?),
-        QueryMsg::AllState {} => to_binary(&query_all_state(deps, env)?),
+        QueryMsg::AllState { room_id } => to_binary(&query_all_state(deps, env, room_id)?),
         QueryMsg::GetRooms { start_after, limit } => {
             to_binary(&query_get_rooms(deps, start_after, limit)?)
         }
+        QueryMsg::CloseableRounds { start_after, limit } => {
+            to_binary(&query_closeable_rounds(deps, env, start_after, limit)?)
+        }
         QueryMsg::GetPlayersForOneRoundOneRoom {
             room_id,
             round_id,
             start_after,
             limit,
-        } => to_binary(&query_get_players_for_one_round_one_room(
-            deps,
+        }
+        | QueryMsg::GetGameInfoForRound {
             room_id,
             round_id,
             start_after,
             limit,
-        )?),
-        QueryMsg::GetGameInfoForRound {
-            round_id,
-            start_after,
-            limit,
-        } => to_binary(&query_get_game_info_for_round(
+        } => to_binary(&query_get_players_for_one_round_one_room(
             deps,
+            room_id,
             round_id,
             start_after,
             limit,
@@ -61,12 +68,43 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             limit,
         )?),
         QueryMsg::GetMaximumWithdrawlFromRoom { room_id } => {
-            to_binary(&query_maximum_withdrwal(deps, env, room_id)?)
+            to_binary(&query_maximum_withdrwal(deps, room_id)?)
+        }
+        QueryMsg::GetRoundExposure { room_id, round_id } => {
+            to_binary(&query_round_exposure(deps, room_id, round_id)?)
+        }
+        QueryMsg::MaxAllowedBet { room_id, direction } => {
+            to_binary(&query_max_allowed_bet(deps, room_id, direction)?)
+        }
+        QueryMsg::SimulateBet {
+            room_id,
+            player,
+            bet_info,
+        } => to_binary(&query_simulate_bet(deps, env, room_id, player, bet_info)?),
+        QueryMsg::GetLiquidity { room_id, provider } => {
+            to_binary(&query_liquidity(deps, room_id, provider)?)
+        }
+        QueryMsg::GetWithdrawalQueue {
+            room_id,
+            start_after,
+            limit,
+        } => to_binary(&query_withdrawal_queue(deps, room_id, start_after, limit)?),
+        QueryMsg::GetWinnerRound { room_id, round_id } => {
+            to_binary(&query_winner_round(deps, room_id, round_id)?)
         }
-        QueryMsg::GetWinnerRound { round_id } => to_binary(&query_winner_round(deps, round_id)?),
-        QueryMsg::GetWinnerLists { start_after, limit } => {
-            to_binary(&query_get_round_lists(deps, start_after, limit)?)
+        QueryMsg::GetWinnerLists {
+            room_id,
+            start_after,
+            limit,
+        } => to_binary(&query_get_round_lists(deps, room_id, start_after, limit)?),
+        QueryMsg::RoundProof { room_id, round_id } => {
+            to_binary(&query_round_proof(deps, room_id, round_id)?)
+        }
+        QueryMsg::GetPendingWinnings { player } => {
+            to_binary(&query_pending_winnings(deps, player)?)
         }
+        QueryMsg::GetBankroll { room_id } => to_binary(&query_bankroll(deps, room_id)?),
+        QueryMsg::GetRoomOdds { room_id } => to_binary(&query_room_odds(deps, room_id)?),
     }
 }
 
@@ -80,20 +118,34 @@ fn query_state(deps: Deps) -> StdResult<StateResponse> {
     Ok(StateResponse { state })
 }
 
-fn query_all_state(deps: Deps, env: Env) -> StdResult<AllStateResponse> {
+fn query_all_state(deps: Deps, env: Env, room_id: u64) -> StdResult<AllStateResponse> {
     let state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
     let crr_time = env.block.time.seconds();
-    let round_start_second =
-        match ROUND_START_SECOND.may_load(deps.storage, &state.living_round.to_string())? {
-            Some(round_second) => round_second,
-            None => 0,
-        };
+    let living_round = LIVING_ROUND
+        .may_load(deps.storage, &room_id.to_string())?
+        .unwrap_or_default();
+    let round_start =
+        ROUND_START_SECOND.may_load(deps.storage, round_key(room_id, living_round))?;
+    //both are known once the first bet starts the round
+    let room = ROOMS.may_load(deps.storage, &room_id.to_string())?;
+    let (betting_close, earliest_close) = match (&room, round_start) {
+        (Some(room), Some(round_start)) => (
+            Some(room.betting_close(round_start)),
+            Some(room.round_close(round_start)),
+        ),
+        _ => (None, None),
+    };
     Ok(AllStateResponse {
         state,
         config,
         crr_time,
-        round_start_second,
+        room_id,
+        living_round,
+        round_start_second: round_start.unwrap_or_default(),
+        betting_close,
+        earliest_close,
+        settlement: SETTLEMENT.may_load(deps.storage, &room_id.to_string())?,
     })
 }
 
@@ -107,6 +159,14 @@ fn query_room_info(deps: Deps, room_id: u64) -> StdResult<RoomInfoResponse> {
             room_id: room_id.to_string(),
             max_bet: room.max_bet,
             min_bet: room.min_bet,
+            next_round_seconds: room.next_round_seconds,
+            bet_lock_seconds: room.bet_lock_seconds,
+            no_more_bets_seconds: room.no_more_bets_seconds,
+            keeper_reward: room.keeper_reward,
+            risk_fraction: room.risk_fraction,
+            wheel: room.wheel,
+            payout_table: room.payout_table,
+            zero_rule: room.zero_rule,
         },
     })
 }
@@ -130,63 +190,104 @@ fn query_get_rooms(
                 room_id: item.0,
                 max_bet: item.1.max_bet,
                 min_bet: item.1.min_bet,
+                next_round_seconds: item.1.next_round_seconds,
+                bet_lock_seconds: item.1.bet_lock_seconds,
+                no_more_bets_seconds: item.1.no_more_bets_seconds,
+                keeper_reward: item.1.keeper_reward,
+                risk_fraction: item.1.risk_fraction,
+                wheel: item.1.wheel,
+                payout_table: item.1.payout_table,
+                zero_rule: item.1.zero_rule,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
     Ok(RoomsInfoResponse { rooms })
 }
 
-fn query_get_players_for_one_round_one_room(
+/// Living rounds of the next `limit` rooms that anyone can close now, with what the closer earns.
+fn query_closeable_rounds(
     deps: Deps,
-    room_id: u64,
-    round_id: u64,
-    start_after: Option<String>,
+    env: Env,
+    start_after: Option<u64>,
     limit: Option<u32>,
-) -> StdResult<BetsInfoResponse> {
+) -> StdResult<CloseableRoundsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let crr_time = env.block.time.seconds();
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
-    let start = if let Some(start) = start_after {
-        Some(Bound::exclusive(bet_info_key(
-            room_id,
-            round_id,
-            &deps.api.addr_validate(&start)?,
-        )))
-    } else {
-        None
-    };
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.to_string().into()));
 
-    let bets_info = bet_info_storage()
-        .idx
-        .room_round_players
-        .prefix((room_id.to_string(), round_id.to_string()))
+    let rooms = ROOMS
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
-        .map(|item| item.map(|(_, b)| b))
         .collect::<StdResult<Vec<_>>>()?;
-
-    Ok(BetsInfoResponse { bets_info })
+    let mut rounds = vec![];
+    for (room_id, room) in rooms {
+        let living_round = LIVING_ROUND.load(deps.storage, &room_id)?;
+        let key = (room_id.clone(), living_round.to_string());
+        //a round without bets has nothing to close
+        let round_start = match ROUND_START_SECOND.may_load(deps.storage, key.clone())? {
+            Some(round_start) => round_start,
+            None => continue,
+        };
+        let drawing_since = ROUND_DRAWING.may_load(deps.storage, key.clone())?;
+        let drand_round = ROUND_DRAND.may_load(deps.storage, key)?;
+        //without the secret or the randomness, closing it only refunds its bets
+        let (closeable_since, void) = match (&config.randomness, drawing_since) {
+            (_, Some(drawing_since)) => (drawing_since + config.reveal_timeout_seconds, true),
+            (RandomnessSource::CommitReveal {}, None) => (
+                room.round_close(round_start) + config.reveal_timeout_seconds,
+                true,
+            ),
+            //the round closes with the beacon bound to it, once drand publishes it
+            (
+                RandomnessSource::Drand {
+                    genesis_time,
+                    period,
+                    ..
+                },
+                None,
+            ) => match drand_round {
+                Some(drand_round) => (drand_round_time(drand_round, *genesis_time, *period), false),
+                None => (room.round_close(round_start), false),
+            },
+            _ => (room.round_close(round_start), false),
+        };
+        if crr_time < closeable_since {
+            continue;
+        }
+        rounds.push(CloseableRound {
+            room_id: room_id.clone(),
+            round_id: living_round.to_string(),
+            closeable_since,
+            drand_round,
+            void,
+            keeper_reward: if void { None } else { room.keeper_reward },
+        });
+    }
+    Ok(CloseableRoundsResponse { rounds })
 }
 
-fn query_get_game_info_for_round(
+fn query_get_players_for_one_round_one_room(
     deps: Deps,
+    room_id: u64,
     round_id: u64,
-    start_after: Option<RoundOffset>,
+    start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<BetsInfoResponse> {
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
-    let start = if let Some(start) = start_after {
-        Some(Bound::exclusive(bet_info_key(
-            start.room_id,
+    let start = match start_after {
+        Some(start) => Some(Bound::exclusive(bet_info_key(
+            room_id,
             round_id,
-            &start.player,
-        )))
-    } else {
-        None
+            &deps.api.addr_validate(&start)?,
+        ))),
+        None => None,
     };
 
     let bets_info = bet_info_storage()
         .idx
-        .round_id
-        .prefix(round_id.to_string())
+        .room_round_players
+        .prefix((room_id.to_string(), round_id.to_string()))
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|item| item.map(|(_, b)| b))
@@ -203,11 +304,7 @@ fn query_player_infos_for_room(
     limit: Option<u32>,
 ) -> StdResult<BetsInfoResponse> {
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
-    let start = if let Some(start) = start_after {
-        Some(Bound::exclusive(bet_info_key(room_id, start, &player)))
-    } else {
-        None
-    };
+    let start = start_after.map(|start| Bound::exclusive(bet_info_key(room_id, start, &player)));
 
     let bets_info = bet_info_storage()
         .idx
@@ -221,12 +318,11 @@ fn query_player_infos_for_room(
     Ok(BetsInfoResponse { bets_info })
 }
 
-fn query_maximum_withdrwal(deps: Deps, env: Env, room_id: u64) -> StdResult<WithdrawResponse> {
+fn query_maximum_withdrwal(deps: Deps, room_id: u64) -> StdResult<WithdrawResponse> {
     let room = ROOMS.may_load(deps.storage, &room_id.to_string())?;
     match room {
-        Some(room_info) => {
-            let contract_address = env.contract.address;
-            let withdrawal_amount = get_withdrawal_amount(deps, &room_info, &contract_address)?;
+        Some(_room_info) => {
+            let withdrawal_amount = get_withdrawal_amount(deps, room_id)?;
             Ok(WithdrawResponse {
                 amount: withdrawal_amount,
             })
@@ -237,41 +333,221 @@ fn query_maximum_withdrwal(deps: Deps, env: Env, room_id: u64) -> StdResult<With
     }
 }
 
-fn query_winner_round(deps: Deps, round_id: u64) -> StdResult<WinnerResponse> {
-    let winner = WINNERNUMBER.may_load(deps.storage, &round_id.to_string())?;
-    match winner {
-        Some(winner) => Ok(WinnerResponse {
-            winner: Winner {
-                winner,
-                round_id: round_id.to_string(),
-            },
-        }),
-        None => Ok(WinnerResponse {
-            winner: Winner {
-                winner: 40,
-                round_id: round_id.to_string(),
-            },
-        }),
-    }
+fn query_round_exposure(
+    deps: Deps,
+    room_id: u64,
+    round_id: u64,
+) -> StdResult<RoundExposureResponse> {
+    let room = ROOMS.load(deps.storage, &room_id.to_string())?;
+    let exposure = load_round_exposure(deps.storage, room_id, round_id, &room)?;
+    Ok(RoundExposureResponse {
+        room_id: room_id.to_string(),
+        round_id: round_id.to_string(),
+        reserve: exposure.reserve(),
+        exposure,
+    })
 }
 
-fn query_get_round_lists(
+fn query_max_allowed_bet(
+    deps: Deps,
+    room_id: u64,
+    direction: Direction,
+) -> StdResult<MaxAllowedBetResponse> {
+    let room = ROOMS.load(deps.storage, &room_id.to_string())?;
+    Ok(MaxAllowedBetResponse {
+        room_id: room_id.to_string(),
+        max_bet: get_max_allowed_bet(deps, room_id, &room, &direction)?,
+        direction,
+    })
+}
+
+fn query_simulate_bet(
+    deps: Deps,
+    env: Env,
+    room_id: u64,
+    player: Addr,
+    bet_info: Vec<BetConfig>,
+) -> StdResult<SimulateBetResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let total_stake = bet_info.iter().map(|bet| bet.amount).sum();
+    //the payouts of chips the room does not know are left out, the error tells why
+    let payouts = match ROOMS.may_load(deps.storage, &room_id.to_string())? {
+        Some(room) => pocket_winnings(&bet_info, &room, config.platform_fee)
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, payout)| !payout.is_zero())
+            .map(|(pocket, payout)| PocketPayout {
+                pocket: pocket as u32,
+                payout,
+            })
+            .collect(),
+        None => vec![],
+    };
+    let error = simulate_bet(deps, env.block.time.seconds(), &player, room_id, bet_info)
+        .err()
+        .map(|err| err.to_string());
+    Ok(SimulateBetResponse {
+        room_id: room_id.to_string(),
+        player: player.to_string(),
+        success: error.is_none(),
+        error,
+        total_stake,
+        payouts,
+    })
+}
+
+fn query_liquidity(deps: Deps, room_id: u64, provider: Addr) -> StdResult<LiquidityResponse> {
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    let shares = LP_SHARES
+        .may_load(
+            deps.storage,
+            lp_shares_key(&provider, room_id, bankroll.share_epoch),
+        )?
+        .unwrap_or_default();
+    let value = if shares.is_zero() {
+        Uint128::zero()
+    } else {
+        bankroll
+            .deposited
+            .multiply_ratio(shares, bankroll.total_shares)
+    };
+    Ok(LiquidityResponse {
+        room_id: room_id.to_string(),
+        shares,
+        total_shares: bankroll.total_shares,
+        value,
+        withdrawable: get_provider_withdrawal_amount(deps, room_id, &provider)?,
+    })
+}
+
+fn query_withdrawal_queue(
     deps: Deps,
+    room_id: u64,
     start_after: Option<u64>,
     limit: Option<u32>,
-) -> StdResult<WinnerListResponse> {
+) -> StdResult<WithdrawalQueueResponse> {
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
     let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
-    let start = start_after.map(|s| Bound::ExclusiveRaw(s.to_string().into()));
+    let start = start_after.map(Bound::exclusive);
 
-    let winner_list = WINNERNUMBER
-        .range(deps.storage, start, None, Order::Descending)
+    let withdrawals = WITHDRAWAL_QUEUE
+        .prefix(room_id.to_string())
+        .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
         .map(|res| {
-            res.map(|item| Winner {
-                round_id: item.0,
-                winner: item.1,
+            res.map(|(id, request)| QueuedWithdrawal {
+                id,
+                //the shares escrowed before the bankroll was depleted are worthless
+                value: if request.share_epoch == bankroll.share_epoch {
+                    bankroll
+                        .deposited
+                        .multiply_ratio(request.shares, bankroll.total_shares)
+                } else {
+                    Uint128::zero()
+                },
+                provider: request.provider,
+                shares: request.shares,
+                requested_at: request.requested_at,
             })
         })
         .collect::<StdResult<Vec<_>>>()?;
+    Ok(WithdrawalQueueResponse {
+        room_id: room_id.to_string(),
+        withdrawals,
+    })
+}
+
+fn query_bankroll(deps: Deps, room_id: u64) -> StdResult<BankrollResponse> {
+    let bankroll = BANKROLL.load(deps.storage, &room_id.to_string())?;
+    Ok(BankrollResponse {
+        room_id: room_id.to_string(),
+        bankroll,
+    })
+}
+
+fn query_winner_round(deps: Deps, room_id: u64, round_id: u64) -> StdResult<WinnerResponse> {
+    let winner = WINNERNUMBER.may_load(deps.storage, round_key(room_id, round_id))?;
+    Ok(WinnerResponse {
+        winner: Winner {
+            winner,
+            void: VOID_ROUNDS.has(deps.storage, round_key(room_id, round_id)),
+            room_id: room_id.to_string(),
+            round_id: round_id.to_string(),
+        },
+    })
+}
+
+fn query_get_round_lists(
+    deps: Deps,
+    room_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<WinnerListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT) as usize;
+    //the round ids are stored as strings, so the rounds are walked by number, not by key:
+    //every round before the living one is either drawn or void
+    let living_round = LIVING_ROUND.load(deps.storage, &room_id.to_string())?;
+    let end = start_after.map_or(living_round, |s| s.min(living_round));
+
+    let mut winner_list = vec![];
+    for round_id in (0..end).rev().take(limit) {
+        let key = round_key(room_id, round_id);
+        let winner = WINNERNUMBER.may_load(deps.storage, key.clone())?;
+        //the rounds closed without a winner are listed with the drawn ones
+        if winner.is_none() && !VOID_ROUNDS.has(deps.storage, key) {
+            continue;
+        }
+        winner_list.push(Winner {
+            room_id: room_id.to_string(),
+            round_id: round_id.to_string(),
+            winner,
+            void: winner.is_none(),
+        });
+    }
     Ok(WinnerListResponse { winner_list })
 }
+
+fn query_round_proof(deps: Deps, room_id: u64, round_id: u64) -> StdResult<RoundProofResponse> {
+    let proof = ROUND_PROOF.load(deps.storage, round_key(room_id, round_id))?;
+    Ok(RoundProofResponse {
+        room_id: room_id.to_string(),
+        round_id: round_id.to_string(),
+        proof,
+    })
+}
+
+fn query_pending_winnings(deps: Deps, player: Addr) -> StdResult<PendingWinningsResponse> {
+    let winnings = CLAIMABLE
+        .prefix(player.to_string())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| {
+            res.map(|item| PendingWinning {
+                room_id: item.0,
+                amount: item.1,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingWinningsResponse { winnings })
+}
+
+fn query_room_odds(deps: Deps, room_id: u64) -> StdResult<RoomOddsResponse> {
+    let room = ROOMS.load(deps.storage, &room_id.to_string())?;
+    let odds = room
+        .payout_table
+        .entries(&room.wheel)
+        .into_iter()
+        .map(|(bet_type, numbers, payout)| BetOdds {
+            bet_type: bet_type.to_string(),
+            numbers,
+            payout,
+            house_edge: Decimal::one()
+                .saturating_sub(room.expected_return(bet_type, numbers, payout)),
+        })
+        .collect();
+    Ok(RoomOddsResponse {
+        room_id: room_id.to_string(),
+        wheel: room.wheel,
+        odds,
+    })
+}
@@ -22,8 +22,8 @@ impl Prng {
         let mut hasher = Sha256::new();
 
         // write input message
-        hasher.update(&seed);
-        hasher.update(&entropy);
+        hasher.update(seed);
+        hasher.update(entropy);
         let hash = hasher.finalize();
 
         let mut hash_bytes = [0u8; 32];
@@ -42,6 +42,14 @@ impl Prng {
     }
 }
 
+/// Draws the winning pocket out of `pockets` from the round seed mixed with the round entropy.
+pub fn rand_generator(seed: &[u8], entropy: &[u8], pockets: u32) -> u32 {
+    let mut prng = Prng::new(seed, entropy);
+    let mut rng = ChaChaRng::from_seed(prng.rand_bytes());
+    let rand_num = rng.next_u32();
+    rand_num % pockets
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
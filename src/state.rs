@@ -1,32 +1,171 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Uint128};
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
-use crate::msg::{BetConfig, Direction};
+use crate::msg::{BetConfig, DrandBeacon};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STATE: Item<State> = Item::new("state");
-pub const MINIMUMRESERVE: Item<Uint128> = Item::new("minimum_reserve");
+pub const BANKROLL: Map<&str, Bankroll> = Map::new("bankroll");
 pub const ROOMS: Map<&str, RoomConfig> = Map::new("rounds");
-pub const WINNERNUMBER: Map<&str, u32> = Map::new("random_winner");
-pub const ROUND_START_SECOND: Map<&str, u64> = Map::new("round_start_second");
+/// living round of each room, every room runs its own round lifecycle
+pub const LIVING_ROUND: Map<&str, u64> = Map::new("living_round");
+pub const WINNERNUMBER: Map<RoundKey, u32> = Map::new("random_winner");
+pub const ROUND_START_SECOND: Map<RoundKey, u64> = Map::new("round_start_second");
+/// sha256 hash of the operator's secret, committed before the round accepts bets
+pub const ROUND_COMMITMENT: Map<RoundKey, Binary> = Map::new("round_commitment");
+/// drand round bound to the game round when it opens, published after betting closes
+pub const ROUND_DRAND: Map<RoundKey, u64> = Map::new("round_drand");
+/// time the randomness was requested from the proxy, the round is drawing until its callback
+pub const ROUND_DRAWING: Map<RoundKey, u64> = Map::new("round_drawing");
+pub const ROUND_PROOF: Map<RoundKey, RoundProof> = Map::new("round_proof");
+/// whoever closed the round, the keeper reward of the room is credited to them on settlement
+pub const ROUND_KEEPER: Map<RoundKey, Addr> = Map::new("round_keeper");
+/// what the room owes on each pocket for the bets of the round, kept up to date on each bet
+pub const ROUND_EXPOSURE: Map<RoundKey, Exposure> = Map::new("round_exposure");
+/// rounds closed without a winner and refunded, with the time they were voided
+pub const VOID_ROUNDS: Map<RoundKey, u64> = Map::new("void_rounds");
+/// LP shares of each provider in a room: (provider, room_id, share_epoch)
+pub const LP_SHARES: Map<LpSharesKey, Uint128> = Map::new("lp_shares");
+/// withdrawals waiting for the exposure of the living round to be released: (room_id, id)
+pub const WITHDRAWAL_QUEUE: Map<(String, u64), WithdrawalRequest> = Map::new("withdrawal_queue");
+/// id of the next queued withdrawal of each room
+pub const NEXT_WITHDRAWAL_ID: Map<&str, u64> = Map::new("next_withdrawal_id");
+/// winnings and refunds credited by closed rounds, waiting to be claimed: (player, room_id)
+pub const CLAIMABLE: Map<(String, String), Uint128> = Map::new("claimable");
+/// closed round of each room whose bets are still being credited
+pub const SETTLEMENT: Map<&str, Settlement> = Map::new("settlement");
+
+// /// Primary key for round data: (room_id, round_id)
+pub type RoundKey = (String, String);
+// /// Convenience round key constructor
+pub fn round_key(room_id: u64, round_id: u64) -> RoundKey {
+    (room_id.to_string(), round_id.to_string())
+}
 
 #[cw_serde]
 pub struct Config {
     pub admin: Addr,
     pub nft_contract: Addr,
-    pub next_round_seconds: u64,
     pub distributor: Addr,
     pub platform_fee: Decimal,
+    /// seconds after the round finishes that the operator has to reveal the secret,
+    /// once passed the round can be closed without a reveal and all bets are refunded,
+    /// unless its drand beacon is already due
+    pub reveal_timeout_seconds: u64,
+    /// seconds after the round finishes before any of its players can refund it,
+    /// when nobody closes it or its randomness never comes
+    pub refund_timeout_seconds: u64,
+    /// house edge every bet of a payout table must keep at least
+    pub min_house_edge: Decimal,
+    pub randomness: RandomnessSource,
+}
+
+#[cw_serde]
+pub enum RandomnessSource {
+    /// The distributor commits a secret hash before the round and reveals it on close
+    CommitReveal {},
+    /// Verified drand beacon, `genesis_time` and `period` are in seconds
+    Drand {
+        pubkey: Binary,
+        genesis_time: u64,
+        period: u64,
+    },
+    /// External randomness provider, answers with `ExecuteMsg::ReceiveRandomness`.
+    /// The provider is trusted: a round whose answer comes later than `reveal_timeout_seconds`
+    /// can be voided by anyone, so a provider that sees its randomness first can hold it back
+    /// and void the rounds it does not like
+    Proxy { contract: Addr },
 }
 
 #[cw_serde]
 pub struct State {
-    pub living_round: u64,
     pub is_haulted: bool,
     pub room_id: u64,
 }
 
+/// Every input of the draw of a round, so anyone can recompute the winning pocket
+/// with `rand::rand_generator(seed, entropy, pockets)`
+#[cw_serde]
+pub struct RoundProof {
+    pub seed: Binary,
+    /// big endian room id followed by the big endian round id
+    pub entropy: Binary,
+    /// sha256(seed) committed before the round for the commit-reveal source
+    pub commitment: Option<Binary>,
+    /// the seed is sha256(signature) of this beacon for the drand source
+    pub beacon: Option<DrandBeacon>,
+    /// the seed is the randomness delivered for this job for the proxy source
+    pub job_id: Option<String>,
+    /// number of pockets of the wheel of the room
+    pub pockets: u32,
+    pub winner: u32,
+}
+
+/// Money of a room, kept apart from the other rooms even when they share a denom
+#[cw_serde]
+#[derive(Default)]
+pub struct Bankroll {
+    /// deposits of the liquidity providers plus the stakes and results of the rounds
+    pub deposited: Uint128,
+    /// worst case payout of the living round, it can not be withdrawn
+    pub locked: Uint128,
+    /// platform fees of settled rounds, waiting to be collected by the distributor
+    pub fees_owed: Uint128,
+    /// LP shares issued to the providers, each share owns the same part of `deposited`
+    pub total_shares: Uint128,
+    /// bumped when a deposit refills a depleted bankroll, the shares of earlier epochs are
+    /// worthless
+    pub share_epoch: u64,
+}
+
+/// Payouts owed by the room for the bets of a round
+#[cw_serde]
+#[derive(Default)]
+pub struct Exposure {
+    /// payout owed if the pocket wins, indexed by pocket
+    pub pockets: Vec<Uint128>,
+    /// stakes of the round, the imprisoned ones included
+    pub total_stake: Uint128,
+}
+
+impl Exposure {
+    /// Reserve the round needs to pay its worst pocket, or all its stakes as the platform fee
+    /// is taken from them when nobody wins
+    pub fn reserve(&self) -> Uint128 {
+        self.pockets
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(self.total_stake)
+    }
+}
+
+/// LP shares escrowed until a settlement pays them out of the unlocked bankroll
+#[cw_serde]
+pub struct WithdrawalRequest {
+    pub provider: Addr,
+    pub shares: Uint128,
+    pub requested_at: u64,
+    /// share epoch of the escrowed shares
+    pub share_epoch: u64,
+}
+
+/// Progress of the settlement of a closed round, bets of the room are blocked until it is done
+#[cw_serde]
+pub struct Settlement {
+    pub round_id: u64,
+    /// none when the round is voided and every stake is refunded
+    pub winner: Option<u32>,
+    /// last player whose bets are credited
+    pub cursor: Option<String>,
+    pub total_bet_amount: Uint128,
+    pub user_winning_amount: Uint128,
+    /// even money stakes imprisoned into the next round by a zero
+    pub imprisoned_amount: Uint128,
+}
+
 #[cw_serde]
 pub struct RoomConfig {
     pub room_name: String,
@@ -34,6 +173,20 @@ pub struct RoomConfig {
     pub nft_id: String,
     pub max_bet: Uint128,
     pub min_bet: Uint128,
+    pub next_round_seconds: u64,
+    /// cancel and modify are refused in the last seconds of the betting time
+    pub bet_lock_seconds: u64,
+    /// "no more bets", bets are refused in the last seconds before the round can close
+    pub no_more_bets_seconds: u64,
+    /// share of the platform fee of a round paid to whoever closes it once the round is past
+    /// its close time, credited to the closer when the round is settled
+    pub keeper_reward: Option<Decimal>,
+    /// when set, the bets placed at once can not pay more than this fraction of the free
+    /// bankroll on any pocket, so the max bet follows the bankroll and its exposure
+    pub risk_fraction: Option<Decimal>,
+    pub wheel: Wheel,
+    pub payout_table: PayoutTable,
+    pub zero_rule: ZeroRule,
 }
 
 #[cw_serde]
@@ -44,6 +197,152 @@ pub struct RoomInfo {
     pub room_id: String,
     pub max_bet: Uint128,
     pub min_bet: Uint128,
+    pub next_round_seconds: u64,
+    /// cancel and modify are refused in the last seconds of the betting time
+    pub bet_lock_seconds: u64,
+    /// "no more bets", bets are refused in the last seconds before the round can close
+    pub no_more_bets_seconds: u64,
+    /// share of the platform fee of a round paid to whoever closes it once the round is past
+    /// its close time, credited to the closer when the round is settled
+    pub keeper_reward: Option<Decimal>,
+    /// when set, the bets placed at once can not pay more than this fraction of the free
+    /// bankroll on any pocket, so the max bet follows the bankroll and its exposure
+    pub risk_fraction: Option<Decimal>,
+    pub wheel: Wheel,
+    pub payout_table: PayoutTable,
+    pub zero_rule: ZeroRule,
+}
+
+#[cw_serde]
+pub enum Wheel {
+    /// single zero, 37 pockets
+    European,
+    /// zero and double zero, 38 pockets, the double zero is pocket 37
+    American,
+}
+
+impl Wheel {
+    pub fn pockets(&self) -> u32 {
+        match self {
+            Wheel::European => 37,
+            Wheel::American => 38,
+        }
+    }
+
+    pub fn zeros(&self) -> u32 {
+        match self {
+            Wheel::European => 1,
+            Wheel::American => 2,
+        }
+    }
+
+    pub fn is_zero(&self, pocket: u32) -> bool {
+        pocket == 0 || (*self == Wheel::American && pocket == 37)
+    }
+}
+
+/// What happens to the even money bets when a zero is drawn
+#[cw_serde]
+pub enum ZeroRule {
+    /// they are lost like every other bet
+    Standard,
+    /// half of the stake is returned
+    LaPartage,
+    /// they stay on the table for the next round, where a win only returns the stake
+    EnPrison,
+}
+
+impl RoomConfig {
+    /// The round can be closed from this time on
+    pub fn round_close(&self, round_start: u64) -> u64 {
+        round_start + self.next_round_seconds
+    }
+
+    /// No more bets are accepted after this time
+    pub fn betting_close(&self, round_start: u64) -> u64 {
+        self.round_close(round_start) - self.no_more_bets_seconds
+    }
+
+    /// Average return of a stake of 1 on a bet covering `numbers` pockets, the zero rule of
+    /// the room included for the even money bets
+    pub fn expected_return(&self, bet_type: &str, numbers: u32, payout: Decimal) -> Decimal {
+        let pockets = self.wheel.pockets();
+        let mut expected_return = payout * Decimal::from_ratio(numbers, pockets);
+        if bet_type == "even_money" {
+            let zero_hit = Decimal::from_ratio(self.wheel.zeros(), pockets);
+            expected_return += match self.zero_rule {
+                ZeroRule::Standard => Decimal::zero(),
+                ZeroRule::LaPartage => zero_hit * Decimal::percent(50),
+                //the imprisoned stake comes back if it wins the next round
+                ZeroRule::EnPrison => zero_hit * Decimal::from_ratio(numbers, pockets),
+            };
+        }
+        expected_return
+    }
+}
+
+/// Total return of a winning bet of each type with the stake included,
+/// e.g. 36 for a single paying 35 to 1
+#[cw_serde]
+pub struct PayoutTable {
+    /// odd, even, halves and colors
+    pub even_money: Decimal,
+    pub column: Decimal,
+    pub dozen: Decimal,
+    pub single: Decimal,
+    pub split: Decimal,
+    pub street: Decimal,
+    pub corner: Decimal,
+    pub six_line: Decimal,
+    pub trio: Decimal,
+    pub basket: Decimal,
+    pub top_line: Decimal,
+}
+
+impl PayoutTable {
+    /// the payouts of the standard table for this wheel
+    pub fn standard(wheel: &Wheel) -> Self {
+        let payout = |ratio: u128| Decimal::from_ratio(ratio, 1u128);
+        PayoutTable {
+            even_money: payout(2),
+            column: payout(3),
+            dozen: payout(3),
+            single: payout(36),
+            split: payout(18),
+            street: payout(12),
+            corner: payout(9),
+            six_line: payout(6),
+            trio: payout(12),
+            basket: payout(12),
+            top_line: match wheel {
+                Wheel::European => payout(9),
+                Wheel::American => payout(7),
+            },
+        }
+    }
+
+    /// (bet type, numbers covered, payout) of every bet available on the wheel
+    pub fn entries(&self, wheel: &Wheel) -> Vec<(&'static str, u32, Decimal)> {
+        let mut entries = vec![
+            ("even_money", 18, self.even_money),
+            ("column", 12, self.column),
+            ("dozen", 12, self.dozen),
+            ("single", 1, self.single),
+            ("split", 2, self.split),
+            ("street", 3, self.street),
+            ("corner", 4, self.corner),
+            ("six_line", 6, self.six_line),
+            ("trio", 3, self.trio),
+        ];
+        match wheel {
+            Wheel::European => entries.push(("top_line", 4, self.top_line)),
+            Wheel::American => {
+                entries.push(("basket", 3, self.basket));
+                entries.push(("top_line", 5, self.top_line));
+            }
+        }
+        entries
+    }
 }
 
 #[cw_serde]
@@ -61,6 +360,14 @@ pub struct BetInfo {
     pub room_id: String,
     pub bet_info: Vec<BetConfig>,
     pub bet_time: u64,
+    /// even money bets imprisoned by the zero of the previous round, a win returns the stake
+    pub imprisoned: Vec<BetConfig>,
+}
+
+/// Primary key for LP shares: (provider, room_id, share_epoch)
+pub type LpSharesKey = (String, String, u64);
+pub fn lp_shares_key(provider: &Addr, room_id: u64, share_epoch: u64) -> LpSharesKey {
+    (provider.to_string(), room_id.to_string(), share_epoch)
 }
 
 // /// Primary key for betinfo: (room_id, round_id, player)
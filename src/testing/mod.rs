@@ -1 +1,2 @@
+mod randomness_proxy;
 mod tests;
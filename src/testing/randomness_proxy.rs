@@ -0,0 +1,64 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, Response, StdError,
+    StdResult, WasmMsg,
+};
+use cw_storage_plus::Map;
+
+use crate::msg::{ExecuteMsg as RouletteExecuteMsg, RandomnessCallback};
+
+/// job id => requesting contract
+const JOBS: Map<&str, String> = Map::new("jobs");
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    GetNextRandomness {
+        job_id: String,
+    },
+    /// test helper, delivers the randomness of a pending job to its requester
+    Deliver {
+        job_id: String,
+        randomness: HexBinary,
+    },
+}
+
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::GetNextRandomness { job_id } => {
+            JOBS.save(deps.storage, &job_id, &info.sender.to_string())?;
+            Ok(Response::new())
+        }
+        ExecuteMsg::Deliver { job_id, randomness } => {
+            let requester = JOBS.load(deps.storage, &job_id)?;
+            JOBS.remove(deps.storage, &job_id);
+            let callback_msg = WasmMsg::Execute {
+                contract_addr: requester,
+                msg: to_binary(&RouletteExecuteMsg::ReceiveRandomness {
+                    callback: RandomnessCallback {
+                        job_id,
+                        published: env.block.time,
+                        randomness,
+                    },
+                })?,
+                funds: vec![],
+            };
+            Ok(Response::new().add_message(callback_msg))
+        }
+    }
+}
+
+pub fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+    Err(StdError::generic_err("not supported"))
+}
@@ -2,8 +2,8 @@ use std::vec;
 
 // use cosmwasm_std::testing::mock_env;
 use cosmwasm_std::{
-    testing::mock_env, Addr, BlockInfo, Coin, Decimal, Empty, StdResult, Timestamp, Uint128,
-    WasmMsg,
+    to_binary, Addr, Binary, BlockInfo, Coin, Decimal, Empty, HexBinary, StdError, StdResult,
+    Timestamp, Uint128,
 };
 
 use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
@@ -14,16 +14,28 @@ use cw721_base::{
 use cw_multi_test::{App, BankSudo, Contract, ContractWrapper, Executor, SudoMsg};
 
 use crate::{
+    error::ContractError,
     msg::{
-        BetConfig, BetsInfoResponse, ConfigResponse, Direction, ExecuteMsg, InstantiateMsg,
-        QueryMsg, RoomInfoResponse, RoomsInfoResponse, RoundOffset, WinnerListResponse,
-        WinnerResponse, WithdrawResponse,
+        AllStateResponse, BankrollResponse, BetConfig, BetsInfoResponse, CallBetKind,
+        CloseableRound, CloseableRoundsResponse, ConfigResponse, Direction, DrandBeacon,
+        ExecuteMsg, InstantiateMsg, LiquidityResponse, MaxAllowedBetResponse, PendingWinning,
+        PendingWinningsResponse, PocketPayout, QueryMsg, QueuedWithdrawal, RandomnessCallback,
+        ReceiveMsg, RoomInfoResponse, RoomOddsResponse, RoomsInfoResponse, RoundExposureResponse,
+        RoundProofResponse, SimulateBetResponse, WinnerListResponse, WinnerResponse,
+        WithdrawResponse, WithdrawalQueueResponse,
+    },
+    rand::{rand_generator, sha_256},
+    state::{
+        AssetInfo, Bankroll, Config, PayoutTable, RandomnessSource, RoomConfig, Wheel, ZeroRule,
     },
-    state::{AssetInfo, Config, RoomConfig},
 };
 
 use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
 
+use super::randomness_proxy::{
+    ExecuteMsg as ProxyExecuteMsg, InstantiateMsg as ProxyInstantiateMsg,
+};
+
 fn mock_app() -> App {
     App::default()
 }
@@ -55,19 +67,31 @@ pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+pub fn randomness_proxy_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        super::randomness_proxy::execute,
+        super::randomness_proxy::instantiate,
+        super::randomness_proxy::query,
+    );
+    Box::new(contract)
+}
+
 fn init_roulette_contract(router: &mut App, nft_address: Addr) -> Addr {
     let msg = InstantiateMsg {
         config: Config {
             admin: Addr::unchecked("admin"),
             nft_contract: nft_address,
-            next_round_seconds: 120,
             distributor: Addr::unchecked("distributor"),
-            platform_fee: Decimal::from_ratio(40 as u128, 100 as u128),
+            platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+            reveal_timeout_seconds: 60,
+            refund_timeout_seconds: 600,
+            min_house_edge: Decimal::zero(),
+            randomness: RandomnessSource::CommitReveal {},
         },
     };
     let roulette_id = router.store_code(contract_roulette());
 
-    let roulette_contract = router
+    router
         .instantiate_contract(
             roulette_id,
             Addr::unchecked("admin"),
@@ -76,9 +100,7 @@ fn init_roulette_contract(router: &mut App, nft_address: Addr) -> Addr {
             "roulette",
             Some("admin".to_string()),
         )
-        .unwrap();
-
-    roulette_contract
+        .unwrap()
 }
 
 fn init_cw20_contract(router: &mut App, roulette_address: &Addr) -> Addr {
@@ -109,7 +131,7 @@ fn init_cw20_contract(router: &mut App, roulette_address: &Addr) -> Addr {
     };
     let roulette_id = router.store_code(cw20_contract());
 
-    let roulette_contract = router
+    router
         .instantiate_contract(
             roulette_id,
             Addr::unchecked("admin"),
@@ -118,9 +140,22 @@ fn init_cw20_contract(router: &mut App, roulette_address: &Addr) -> Addr {
             "roulette",
             Some("admin".to_string()),
         )
-        .unwrap();
+        .unwrap()
+}
+
+fn init_randomness_proxy_contract(router: &mut App) -> Addr {
+    let proxy_id = router.store_code(randomness_proxy_contract());
 
-    roulette_contract
+    router
+        .instantiate_contract(
+            proxy_id,
+            Addr::unchecked("admin"),
+            &ProxyInstantiateMsg {},
+            &[],
+            "randomness_proxy",
+            None,
+        )
+        .unwrap()
 }
 
 fn init_cw721_contract_and_mint(router: &mut App) -> Addr {
@@ -176,16 +211,6 @@ fn init_cw721_contract_and_mint(router: &mut App) -> Addr {
 }
 
 fn mint_gaming_tokens_for_users(router: &mut App, roulette_address: &Addr) -> StdResult<()> {
-    router
-        .sudo(SudoMsg::Bank(BankSudo::Mint {
-            to_address: "admin".to_string(),
-            amount: vec![Coin {
-                denom: "usei".to_string(),
-                amount: Uint128::new(10000),
-            }],
-        }))
-        .unwrap();
-
     router
         .sudo(SudoMsg::Bank(BankSudo::Mint {
             to_address: "user1".to_string(),
@@ -211,15 +236,20 @@ fn mint_gaming_tokens_for_users(router: &mut App, roulette_address: &Addr) -> St
             to_address: "sei_admin".to_string(),
             amount: vec![Coin {
                 denom: "usei".to_string(),
-                amount: Uint128::new(10000),
+                amount: Uint128::new(20000),
             }],
         }))
         .unwrap();
 
+    //the owner of the SEI room funds its bankroll
     router
-        .send_tokens(
-            Addr::unchecked("admin"),
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
             roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 1,
+                amount: Uint128::new(10000),
+            },
             &[Coin {
                 denom: "usei".to_string(),
                 amount: Uint128::new(10000),
@@ -230,6 +260,50 @@ fn mint_gaming_tokens_for_users(router: &mut App, roulette_address: &Addr) -> St
     Ok(())
 }
 
+fn commit_round(router: &mut App, roulette_address: &Addr, room_id: u64, secret: &[u8]) {
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CommitRound {
+                room_id,
+                commitment: Binary::from(sha_256(secret).to_vec()),
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+fn settle_round(router: &mut App, roulette_address: &Addr, room_id: u64, round_id: u64) {
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            roulette_address.clone(),
+            &ExecuteMsg::SettleRound {
+                room_id,
+                round_id,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap();
+}
+
+/// the pocket the committed secret draws for this round of this room
+fn winning_pocket(room_id: u64, round_id: u64, secret: &[u8]) -> u32 {
+    let entropy = [room_id.to_be_bytes(), round_id.to_be_bytes()].concat();
+    rand_generator(secret, &entropy, 38)
+}
+
+/// a secret whose winning pocket on a wheel of `pockets` is accepted
+fn find_secret(room_id: u64, round_id: u64, pockets: u32, accept: impl Fn(u32) -> bool) -> Vec<u8> {
+    let entropy = [room_id.to_be_bytes(), round_id.to_be_bytes()].concat();
+    (0..)
+        .map(|i| format!("secret_{}", i).into_bytes())
+        .find(|secret| accept(rand_generator(secret, &entropy, pockets)))
+        .unwrap()
+}
+
 fn init_two_rooms(
     router: &mut App,
     roulette_address: &Addr,
@@ -244,6 +318,14 @@ fn init_two_rooms(
             nft_id: "SEI".to_string(),
             max_bet: Uint128::new(100000),
             min_bet: Uint128::new(100),
+            next_round_seconds: 120,
+            bet_lock_seconds: 30,
+            no_more_bets_seconds: 10,
+            keeper_reward: None,
+            risk_fraction: None,
+            wheel: Wheel::American,
+            payout_table: PayoutTable::standard(&Wheel::American),
+            zero_rule: ZeroRule::Standard,
         },
     };
 
@@ -265,6 +347,14 @@ fn init_two_rooms(
             nft_id: "TEST".to_string(),
             max_bet: Uint128::new(100000),
             min_bet: Uint128::new(100),
+            next_round_seconds: 120,
+            bet_lock_seconds: 30,
+            no_more_bets_seconds: 10,
+            keeper_reward: None,
+            risk_fraction: None,
+            wheel: Wheel::American,
+            payout_table: PayoutTable::standard(&Wheel::American),
+            zero_rule: ZeroRule::Standard,
         },
     };
 
@@ -277,6 +367,20 @@ fn init_two_rooms(
         )
         .unwrap();
 
+    //the owner of the TEST room funds its bankroll
+    router
+        .execute_contract(
+            Addr::unchecked("test_admin"),
+            token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(5000),
+                msg: to_binary(&ReceiveMsg::Deposit { room_id: 2 })?,
+            },
+            &[],
+        )
+        .unwrap();
+
     Ok(())
 }
 
@@ -289,11 +393,33 @@ fn test_update_config() {
     let new_config = Config {
         admin: Addr::unchecked("new_admin"),
         nft_contract: Addr::unchecked("nft_contract"),
-        next_round_seconds: 120,
         distributor: Addr::unchecked("distributor"),
-        platform_fee: Decimal::from_ratio(40 as u128, 100 as u128),
+        platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+        reveal_timeout_seconds: 60,
+        refund_timeout_seconds: 600,
+        min_house_edge: Decimal::zero(),
+        randomness: RandomnessSource::CommitReveal {},
     };
 
+    //the players could refund a round before the operator has to reveal it
+    let err = router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    refund_timeout_seconds: 30,
+                    ..new_config.clone()
+                },
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidTimeouts {}
+    );
+
     router
         .execute_contract(
             Addr::unchecked("admin"),
@@ -313,9 +439,12 @@ fn test_update_config() {
         Config {
             admin: Addr::unchecked("new_admin"),
             nft_contract: Addr::unchecked("nft_contract"),
-            next_round_seconds: 120,
             distributor: Addr::unchecked("distributor"),
-            platform_fee: Decimal::from_ratio(40 as u128, 100 as u128),
+            platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+            reveal_timeout_seconds: 60,
+            refund_timeout_seconds: 600,
+            min_house_edge: Decimal::zero(),
+            randomness: RandomnessSource::CommitReveal {},
         }
     );
 }
@@ -335,6 +464,14 @@ fn test_add_room() {
             nft_id: "SEI".to_string(),
             max_bet: Uint128::new(100000),
             min_bet: Uint128::new(100),
+            next_round_seconds: 120,
+            bet_lock_seconds: 0,
+            no_more_bets_seconds: 0,
+            keeper_reward: None,
+            risk_fraction: None,
+            wheel: Wheel::American,
+            payout_table: PayoutTable::standard(&Wheel::American),
+            zero_rule: ZeroRule::Standard,
         },
     };
 
@@ -373,6 +510,8 @@ fn test_bet_with_native_token() {
 
     init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
     mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    commit_round(&mut router, &roulette_address, 2, b"room_2_round_0_secret");
 
     let bet_msg = ExecuteMsg::Bet {
         room_id: 1,
@@ -380,6 +519,7 @@ fn test_bet_with_native_token() {
             direction: Direction::FirstHalf,
             amount: Uint128::new(200),
         }],
+        replace: None,
     };
 
     router
@@ -394,27 +534,17 @@ fn test_bet_with_native_token() {
         )
         .unwrap();
 
-    router
-        .execute_contract(
-            Addr::unchecked("user2"),
-            token_address.clone(),
-            &Cw20ExecuteMsg::IncreaseAllowance {
-                spender: roulette_address.to_string(),
-                amount: Uint128::new(200),
-                expires: None,
-            },
-            &[],
-        )
-        .unwrap();
-
+    let bet_info = vec![BetConfig {
+        direction: Direction::FirstOfThird,
+        amount: Uint128::new(200),
+    }];
     let bet_msg = ExecuteMsg::Bet {
         room_id: 2,
-        bet_info: vec![BetConfig {
-            direction: Direction::FirstOfThird,
-            amount: Uint128::new(200),
-        }],
+        bet_info: bet_info.clone(),
+        replace: None,
     };
-    router
+    //native coins can not be sent along a bet in a cw20 room
+    let err = router
         .execute_contract(
             Addr::unchecked("user2"),
             roulette_address.clone(),
@@ -424,9 +554,65 @@ fn test_bet_with_native_token() {
                 amount: Uint128::new(200),
             }],
         )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnexpectedFunds {}
+    );
+    //cw20 bets are only accepted through the Send hook of the token
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &bet_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Cw20SendRequired {}
+    );
+
+    let bet_msg = to_binary(&ReceiveMsg::Bet {
+        room_id: 2,
+        bet_info,
+        replace: None,
+    })
+    .unwrap();
+    let other_token_address = init_cw20_contract(&mut router, &roulette_address);
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            other_token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(200),
+                msg: bet_msg.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::IncorrectCw20Token {
+            provided: other_token_address.to_string(),
+            required: token_address.to_string(),
+        }
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(200),
+                msg: bet_msg,
+            },
+            &[],
+        )
         .unwrap();
 
-    let _token_balance: BalanceResponse = router
+    let token_balance: BalanceResponse = router
         .wrap()
         .query_wasm_smart(
             token_address,
@@ -435,6 +621,7 @@ fn test_bet_with_native_token() {
             },
         )
         .unwrap();
+    assert_eq!(token_balance.balance, Uint128::new(15200));
 
     let _room_player: BetsInfoResponse = router
         .wrap()
@@ -465,6 +652,8 @@ fn test_close_round() {
 
     init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
     mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    commit_round(&mut router, &roulette_address, 2, b"room_2_round_0_secret");
 
     let bet_msg = ExecuteMsg::Bet {
         room_id: 1,
@@ -472,6 +661,7 @@ fn test_close_round() {
             direction: Direction::SecondOfThird,
             amount: Uint128::new(100),
         }],
+        replace: None,
     };
 
     router
@@ -492,32 +682,24 @@ fn test_close_round() {
         chain_id: "chain-1".to_string(),
     });
 
-    router
-        .execute_contract(
-            Addr::unchecked("user1"),
-            token_address.clone(),
-            &Cw20ExecuteMsg::IncreaseAllowance {
-                spender: roulette_address.to_string(),
-                amount: Uint128::new(200),
-                expires: None,
-            },
-            &[],
-        )
-        .unwrap();
-
-    let bet_msg = ExecuteMsg::Bet {
+    let bet_msg = ReceiveMsg::Bet {
         room_id: 2,
         bet_info: vec![BetConfig {
             direction: Direction::SecondOfThird,
             amount: Uint128::new(200),
         }],
+        replace: None,
     };
 
     router
         .execute_contract(
             Addr::unchecked("user1"),
-            roulette_address.clone(),
-            &bet_msg,
+            token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(200),
+                msg: to_binary(&bet_msg).unwrap(),
+            },
             &[],
         )
         .unwrap();
@@ -533,11 +715,9 @@ fn test_close_round() {
         .query_wasm_smart(
             roulette_address.clone(),
             &QueryMsg::GetGameInfoForRound {
+                room_id: 1,
                 round_id: 0,
-                start_after: Some(RoundOffset {
-                    room_id: 1,
-                    player: Addr::unchecked("user1"),
-                }),
+                start_after: Some("user1".to_string()),
                 limit: None,
             },
         )
@@ -545,7 +725,11 @@ fn test_close_round() {
 
     println!("bet_info {:?}", bet_info);
 
-    let close_round_msg = ExecuteMsg::CloseRound {};
+    let close_round_msg = ExecuteMsg::CloseRound {
+        room_id: 1,
+        secret: Some(Binary::from(b"round_0_secret".to_vec())),
+        beacon: None,
+    };
     router
         .execute_contract(
             Addr::unchecked("distributor"),
@@ -554,56 +738,85 @@ fn test_close_round() {
             &[],
         )
         .unwrap();
-}
-
-#[test]
-fn test_withdraw() {
-    let mut router = mock_app();
-    let nft_address = init_cw721_contract_and_mint(&mut router);
-    let roulette_address = init_roulette_contract(&mut router, nft_address);
-    let token_address = init_cw20_contract(&mut router, &roulette_address);
-
-    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
-    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
-
-    let bet_msg = ExecuteMsg::Bet {
-        room_id: 1,
-        bet_info: vec![BetConfig {
-            direction: Direction::SecondOfThird,
-            amount: Uint128::new(100),
-        }],
-    };
 
-    router
-        .execute_contract(
-            Addr::unchecked("user1"),
+    let winner: WinnerResponse = router
+        .wrap()
+        .query_wasm_smart(
             roulette_address.clone(),
-            &bet_msg,
-            &[Coin {
-                denom: "usei".to_string(),
-                amount: Uint128::new(100),
-            }],
+            &QueryMsg::GetWinnerRound {
+                room_id: 1,
+                round_id: 0,
+            },
         )
         .unwrap();
+    assert!(winner.winner.winner.unwrap() < 38);
 
-    let maximum_withdrawal_sei: WithdrawResponse = router
+    //anyone can recompute the winner from the round proof
+    let proof: RoundProofResponse = router
         .wrap()
         .query_wasm_smart(
             roulette_address.clone(),
-            &QueryMsg::GetMaximumWithdrawlFromRoom { room_id: 1 },
+            &QueryMsg::RoundProof {
+                room_id: 1,
+                round_id: 0,
+            },
         )
         .unwrap();
+    assert_eq!(proof.proof.seed, Binary::from(b"round_0_secret".to_vec()));
+    assert_eq!(
+        proof.proof.commitment,
+        Some(Binary::from(sha_256(b"round_0_secret").to_vec()))
+    );
+    assert_eq!(
+        Some(rand_generator(
+            &proof.proof.seed,
+            &proof.proof.entropy,
+            proof.proof.pockets
+        )),
+        winner.winner.winner
+    );
 
-    let withdraw_msg = ExecuteMsg::WithdrawFromPool {
-        room_id: 1,
-        amount: Uint128::new(9000),
+    //room 2 opened its round at 100 and keeps running after room 1 closed
+    let close_room_2_msg = ExecuteMsg::CloseRound {
+        room_id: 2,
+        secret: Some(Binary::from(b"room_2_round_0_secret".to_vec())),
+        beacon: None,
     };
+    let err = router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &close_room_2_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundNotFinished {}
+    );
 
+    let room_1_state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 1 })
+        .unwrap();
+    assert_eq!(room_1_state.living_round, 1);
+    let room_2_state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 2 })
+        .unwrap();
+    assert_eq!(room_2_state.living_round, 0);
+    assert_eq!(room_2_state.round_start_second, 100);
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(221),
+        chain_id: "chain-1".to_string(),
+    });
     router
         .execute_contract(
-            Addr::unchecked("sei_admin"),
+            Addr::unchecked("distributor"),
             roulette_address.clone(),
-            &withdraw_msg,
+            &close_room_2_msg,
             &[],
         )
         .unwrap();
@@ -611,20 +824,132 @@ fn test_withdraw() {
     let winner_list: WinnerListResponse = router
         .wrap()
         .query_wasm_smart(
-            roulette_address.clone(),
+            roulette_address,
             &QueryMsg::GetWinnerLists {
+                room_id: 2,
                 start_after: None,
                 limit: None,
             },
         )
         .unwrap();
+    assert_eq!(winner_list.winner_list.len(), 1);
+    assert_eq!(winner_list.winner_list[0].room_id, "2");
+}
 
-    println!("winner_list, {:?}", winner_list)
+#[test]
+fn test_winner_lists_order() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    //rounds 0 to 11 of room 1, the odd ones are void
+    let mut crr_time = 0;
+    for round_id in 0..12u64 {
+        let secret = format!("round_{}_secret", round_id).into_bytes();
+        commit_round(&mut router, &roulette_address, 1, &secret);
+        router
+            .execute_contract(
+                Addr::unchecked("user1"),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id: 1,
+                    bet_info: vec![BetConfig {
+                        direction: Direction::Odd,
+                        amount: Uint128::new(100),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(100),
+                }],
+            )
+            .unwrap();
+        crr_time += 181;
+        router.set_block(BlockInfo {
+            height: 0,
+            time: Timestamp::from_seconds(crr_time),
+            chain_id: "chain-1".to_string(),
+        });
+        let void = round_id % 2 == 1;
+        router
+            .execute_contract(
+                Addr::unchecked("user2"),
+                roulette_address.clone(),
+                &ExecuteMsg::CloseRound {
+                    room_id: 1,
+                    secret: if void {
+                        None
+                    } else {
+                        Some(Binary::from(secret))
+                    },
+                    beacon: None,
+                },
+                &[],
+            )
+            .unwrap();
+        settle_round(&mut router, &roulette_address, 1, round_id);
+    }
+
+    //round 10 comes before round 9, the round ids are ordered by number
+    let winner_list = |router: &App, start_after: Option<u64>| {
+        let res: WinnerListResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetWinnerLists {
+                    room_id: 1,
+                    start_after,
+                    limit: Some(3),
+                },
+            )
+            .unwrap();
+        res.winner_list
+            .into_iter()
+            .map(|winner| (winner.round_id, winner.void))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        winner_list(&router, None),
+        vec![
+            ("11".to_string(), true),
+            ("10".to_string(), false),
+            ("9".to_string(), true),
+        ]
+    );
+    assert_eq!(
+        winner_list(&router, Some(9)),
+        vec![
+            ("8".to_string(), false),
+            ("7".to_string(), true),
+            ("6".to_string(), false),
+        ]
+    );
+    assert_eq!(
+        winner_list(&router, Some(2)),
+        vec![("1".to_string(), true), ("0".to_string(), false)]
+    );
 }
 
 #[test]
-fn test_deposit() {
+fn test_close_round_with_wrong_secret() {
     let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
     let nft_address = init_cw721_contract_and_mint(&mut router);
     let roulette_address = init_roulette_contract(&mut router, nft_address);
     let token_address = init_cw20_contract(&mut router, &roulette_address);
@@ -632,67 +957,3905 @@ fn test_deposit() {
     init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
     mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
 
-    let deposit_msg = ExecuteMsg::Deposit {
+    let bet_msg = ExecuteMsg::Bet {
         room_id: 1,
-        amount: Uint128::new(100),
+        bet_info: vec![BetConfig {
+            direction: Direction::Odd,
+            amount: Uint128::new(100),
+        }],
+        replace: None,
     };
+
+    //nobody can bet before the operator commits the round secret
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &bet_msg,
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundNotCommitted {}
+    );
+
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &bet_msg,
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(b"another_secret".to_vec())),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidReveal {}
+    );
+
+    //the reveal window is still open, so the round can not be voided yet
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: None,
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RevealPending {}
+    );
+}
+
+#[test]
+fn test_inside_bets() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    //only numbers side by side on the table can be split
+    for (direction, msg) in [
+        (
+            Direction::Split {
+                first: 1,
+                second: 5,
+            },
+            "1 and 5 are not adjacent on the table",
+        ),
+        (
+            Direction::Split {
+                first: 3,
+                second: 4,
+            },
+            "3 and 4 are not adjacent on the table",
+        ),
+        (
+            Direction::Corner { id: 3 },
+            "3 is not the lowest number of a corner",
+        ),
+    ] {
+        let err = router
+            .execute_contract(
+                Addr::unchecked("user1"),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id: 1,
+                    bet_info: vec![BetConfig {
+                        direction,
+                        amount: Uint128::new(100),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(100),
+                }],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Std(StdError::generic_err(msg))
+        );
+    }
+
+    let winner = winning_pocket(1, 0, b"round_0_secret");
+    let (first, second) = match winner {
+        0 => (0, 1),
+        37 => (2, 37),
+        w if w % 3 == 0 => (w - 1, w),
+        w => (w, w + 1),
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Split { first, second },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![
+                    BetConfig {
+                        direction: Direction::Corner { id: 32 },
+                        amount: Uint128::new(100),
+                    },
+                    BetConfig {
+                        direction: Direction::SixLine { id: 11 },
+                        amount: Uint128::new(100),
+                    },
+                    BetConfig {
+                        direction: Direction::TopLine,
+                        amount: Uint128::new(100),
+                    },
+                ],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(300),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(b"round_0_secret".to_vec())),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 1, 0);
+
+    //a split pays 17 to 1, so 18 times the bet minus the 40% platform fee is credited
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "1".to_string(),
+            amount: Uint128::new(1080),
+        }]
+    );
+}
+
+#[test]
+fn test_european_wheel() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: RoomConfig {
+                    room_name: "SEI European".to_string(),
+                    game_denom: AssetInfo::NativeToken {
+                        denom: "usei".to_string(),
+                    },
+                    nft_id: "SEI".to_string(),
+                    max_bet: Uint128::new(100000),
+                    min_bet: Uint128::new(100),
+                    next_round_seconds: 120,
+                    bet_lock_seconds: 0,
+                    no_more_bets_seconds: 0,
+                    keeper_reward: None,
+                    risk_fraction: None,
+                    wheel: Wheel::European,
+                    payout_table: PayoutTable::standard(&Wheel::European),
+                    zero_rule: ZeroRule::Standard,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(5000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(5000),
+            }],
+        )
+        .unwrap();
+    commit_round(&mut router, &roulette_address, 3, b"round_0_secret");
+
+    //there is no double zero pocket on the european wheel
+    for (direction, msg) in [
+        (
+            Direction::Single { id: 37 },
+            "The single select parameter must be in the range of 0 to 36",
+        ),
+        (
+            Direction::Basket,
+            "The basket is only available on the american wheel",
+        ),
+        (
+            Direction::Split {
+                first: 2,
+                second: 37,
+            },
+            "2 and 37 are not adjacent on the table",
+        ),
+    ] {
+        let err = router
+            .execute_contract(
+                Addr::unchecked("user1"),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id: 3,
+                    bet_info: vec![BetConfig {
+                        direction,
+                        amount: Uint128::new(100),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(100),
+                }],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::Std(StdError::generic_err(msg))
+        );
+    }
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 3,
+                bet_info: vec![
+                    BetConfig {
+                        direction: Direction::TopLine,
+                        amount: Uint128::new(100),
+                    },
+                    BetConfig {
+                        direction: Direction::Split {
+                            first: 0,
+                            second: 3,
+                        },
+                        amount: Uint128::new(100),
+                    },
+                ],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(200),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 3,
+                secret: Some(Binary::from(b"round_0_secret".to_vec())),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let proof: RoundProofResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::RoundProof {
+                room_id: 3,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(proof.proof.pockets, 37);
+    assert_eq!(
+        proof.proof.winner,
+        rand_generator(b"round_0_secret", &proof.proof.entropy, 37)
+    );
+}
+
+#[test]
+fn test_payout_table() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    let odds: RoomOddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetRoomOdds { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(odds.wheel, Wheel::American);
+    assert_eq!(odds.odds.len(), 11);
+    assert_eq!(odds.odds[3].bet_type, "single");
+    assert_eq!(odds.odds[3].payout, Decimal::from_ratio(36_u128, 1_u128));
+    assert!(odds.odds.iter().all(|bet| !bet.house_edge.is_zero()));
+
+    //a single paying 38 times the stake would give the players the edge on 38 pockets
+    let mut payout_table = PayoutTable::standard(&Wheel::American);
+    payout_table.single = Decimal::from_ratio(39_u128, 1_u128);
+    let err = router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdatePayoutTable {
+                room_id: 1,
+                payout_table: payout_table.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidPayout {
+            bet_type: "single".to_string()
+        }
+    );
+
+    //the admin can require a larger house edge
+    let config: ConfigResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::Config {})
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    min_house_edge: Decimal::percent(5),
+                    ..config.config
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    payout_table.single = Decimal::from_ratio(37_u128, 1_u128);
+    let err = router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdatePayoutTable {
+                room_id: 1,
+                payout_table: payout_table.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidPayout {
+            bet_type: "single".to_string()
+        }
+    );
+
+    payout_table.single = Decimal::from_ratio(35_u128, 1_u128);
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdatePayoutTable {
+                room_id: 1,
+                payout_table: payout_table.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single {
+                        id: winning_pocket(1, 0, b"round_0_secret"),
+                    },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    //the bets of the living round keep the payouts they were accepted with
+    let err = router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdatePayoutTable {
+                room_id: 1,
+                payout_table,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BetsPlaced {}
+    );
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(b"round_0_secret".to_vec())),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 1, 0);
+
+    //35 times the bet minus the 40% platform fee
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending.winnings[0].amount, Uint128::new(2100));
+}
+
+#[test]
+fn test_zero_rules() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    //room 3 plays la partage and room 4 en prison
+    for (room_id, zero_rule) in [(3, ZeroRule::LaPartage), (4, ZeroRule::EnPrison)] {
+        router
+            .execute_contract(
+                Addr::unchecked("admin"),
+                roulette_address.clone(),
+                &ExecuteMsg::AddRoom {
+                    room_info: RoomConfig {
+                        room_name: "SEI French".to_string(),
+                        game_denom: AssetInfo::NativeToken {
+                            denom: "usei".to_string(),
+                        },
+                        nft_id: "SEI".to_string(),
+                        max_bet: Uint128::new(100000),
+                        min_bet: Uint128::new(100),
+                        next_round_seconds: 120,
+                        bet_lock_seconds: 0,
+                        no_more_bets_seconds: 0,
+                        keeper_reward: None,
+                        risk_fraction: None,
+                        wheel: Wheel::European,
+                        payout_table: PayoutTable::standard(&Wheel::European),
+                        zero_rule,
+                    },
+                },
+                &[],
+            )
+            .unwrap();
+        router
+            .execute_contract(
+                Addr::unchecked("sei_admin"),
+                roulette_address.clone(),
+                &ExecuteMsg::Deposit {
+                    room_id,
+                    amount: Uint128::new(5000),
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(5000),
+                }],
+            )
+            .unwrap();
+    }
+
+    //the zero rule lowers the house edge of the even money bets
+    let odds: RoomOddsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetRoomOdds { room_id: 3 },
+        )
+        .unwrap();
+    assert_eq!(odds.odds[0].bet_type, "even_money");
+    assert!(odds.odds[0].house_edge < odds.odds[3].house_edge);
+
+    let zero_secret = |room_id| find_secret(room_id, 0, 37, |pocket| pocket == 0);
+    for room_id in [3, 4] {
+        commit_round(
+            &mut router,
+            &roulette_address,
+            room_id,
+            &zero_secret(room_id),
+        );
+        router
+            .execute_contract(
+                Addr::unchecked("user1"),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id,
+                    bet_info: vec![BetConfig {
+                        direction: Direction::Even,
+                        amount: Uint128::new(200),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(200),
+                }],
+            )
+            .unwrap();
+    }
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    for room_id in [3, 4] {
+        router
+            .execute_contract(
+                Addr::unchecked("distributor"),
+                roulette_address.clone(),
+                &ExecuteMsg::CloseRound {
+                    room_id,
+                    secret: Some(Binary::from(zero_secret(room_id))),
+                    beacon: None,
+                },
+                &[],
+            )
+            .unwrap();
+        settle_round(&mut router, &roulette_address, room_id, 0);
+    }
+
+    //la partage returns half of the stake, en prison keeps the bet for the next round
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "3".to_string(),
+            amount: Uint128::new(100),
+        }]
+    );
+    let players: BetsInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPlayersForOneRoundOneRoom {
+                room_id: 4,
+                round_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(players.bets_info.len(), 1);
+    assert!(players.bets_info[0].bet_info.is_empty());
+    assert_eq!(
+        players.bets_info[0].imprisoned,
+        vec![BetConfig {
+            direction: Direction::Even,
+            amount: Uint128::new(200),
+        }]
+    );
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 4 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.locked, Uint128::new(200));
+    //the imprisoned bet starts the next round, so it can be closed even if nobody bets
+    let state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 4 })
+        .unwrap();
+    assert_eq!(state.living_round, 1);
+    assert_eq!(state.earliest_close, Some(241));
+
+    //the imprisoned player can still bet, and the even number frees the imprisoned stake
+    let even_secret = find_secret(4, 1, 37, |pocket| pocket != 0 && pocket % 2 == 0);
+    commit_round(&mut router, &roulette_address, 4, &even_secret);
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 4,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(242),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 4,
+                secret: Some(Binary::from(even_secret)),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 4, 1);
+
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(pending.winnings[1].room_id, "4");
+    assert_eq!(pending.winnings[1].amount, Uint128::new(200));
+}
+
+#[test]
+fn test_la_partage_exposure() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: RoomConfig {
+                    room_name: "SEI French".to_string(),
+                    game_denom: AssetInfo::NativeToken {
+                        denom: "usei".to_string(),
+                    },
+                    nft_id: "SEI".to_string(),
+                    max_bet: Uint128::new(100000),
+                    min_bet: Uint128::new(100),
+                    next_round_seconds: 120,
+                    bet_lock_seconds: 0,
+                    no_more_bets_seconds: 0,
+                    keeper_reward: None,
+                    risk_fraction: None,
+                    wheel: Wheel::European,
+                    payout_table: PayoutTable::standard(&Wheel::European),
+                    zero_rule: ZeroRule::LaPartage,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(1800),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(1800),
+            }],
+        )
+        .unwrap();
+    let zero_secret = find_secret(3, 0, 37, |pocket| pocket == 0);
+    commit_round(&mut router, &roulette_address, 3, &zero_secret);
+
+    let bet = |router: &mut App, player: &str, direction: Direction, amount: u128| {
+        router
+            .execute_contract(
+                Addr::unchecked(player),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id: 3,
+                    bet_info: vec![BetConfig {
+                        direction,
+                        amount: Uint128::new(amount),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(amount),
+                }],
+            )
+            .unwrap();
+    };
+    bet(&mut router, "user1", Direction::Odd, 1800);
+    bet(&mut router, "user2", Direction::Even, 1800);
+    bet(&mut router, "user1", Direction::Single { id: 0 }, 100);
+
+    //the zero pays the single and returns half of both even money stakes
+    let exposure: RoundExposureResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetRoundExposure {
+                room_id: 3,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(exposure.exposure.pockets[0], Uint128::new(3600 + 900 + 900));
+    assert_eq!(exposure.reserve, Uint128::new(5400));
+
+    //the provider takes everything the round does not lock
+    let withdrawal: WithdrawResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetMaximumWithdrawlFromRoom { room_id: 3 },
+        )
+        .unwrap();
+    assert_eq!(withdrawal.amount, Uint128::new(100));
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 3,
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 3,
+                secret: Some(Binary::from(zero_secret)),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 3, 0);
+
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 3 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::zero());
+    assert_eq!(bankroll.bankroll.fees_owed, Uint128::new(1440));
+    for (player, amount) in [("user1", 2160 + 900), ("user2", 900)] {
+        let pending: PendingWinningsResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetPendingWinnings {
+                    player: Addr::unchecked(player),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending.winnings[0].amount, Uint128::new(amount));
+    }
+}
+
+#[test]
+fn test_call_bets() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: RoomConfig {
+                    room_name: "SEI European".to_string(),
+                    game_denom: AssetInfo::NativeToken {
+                        denom: "usei".to_string(),
+                    },
+                    nft_id: "SEI".to_string(),
+                    max_bet: Uint128::new(100000),
+                    min_bet: Uint128::new(100),
+                    next_round_seconds: 120,
+                    bet_lock_seconds: 0,
+                    no_more_bets_seconds: 0,
+                    keeper_reward: None,
+                    risk_fraction: None,
+                    wheel: Wheel::European,
+                    payout_table: PayoutTable::standard(&Wheel::European),
+                    zero_rule: ZeroRule::Standard,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(5000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(5000),
+            }],
+        )
+        .unwrap();
+
+    //26 is covered by the corner of the voisins du zero and is a neighbour of 0
+    let secret = find_secret(3, 0, 37, |pocket| pocket == 26);
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    commit_round(&mut router, &roulette_address, 3, &secret);
+
+    let bet = |direction, amount| ExecuteMsg::Bet {
+        room_id: 3,
+        bet_info: vec![BetConfig {
+            direction,
+            amount: Uint128::new(amount),
+        }],
+        replace: None,
+    };
+    let voisins = Direction::CallBet {
+        kind: CallBetKind::VoisinsDuZero,
+    };
+    let neighbours = Direction::Neighbours {
+        center: 0,
+        width: 2,
+    };
+
+    //the stake must be spread evenly over the 9 chips of the voisins du zero
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &bet(voisins.clone(), 850),
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(850),
+            }],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "The stake of this bet must be a multiple of 9 to be spread over its chips"
+        ))
+    );
+    //call bets are announced on the european wheel only
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: voisins.clone(),
+                    amount: Uint128::new(900),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(900),
+            }],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Std(StdError::generic_err(
+            "Call bets are only available on the european wheel"
+        ))
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &bet(voisins, 900),
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(900),
+            }],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &bet(neighbours, 500),
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(500),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 3,
+                secret: Some(Binary::from(secret)),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 3, 0);
+
+    //the corner chip takes 200 of the voisins and pays 9 times, the straight chip on 26
+    //takes 100 of the neighbours and pays 36 times, minus the 40% platform fee
+    for (player, amount) in [("user1", 1080), ("user2", 2160)] {
+        let pending: PendingWinningsResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetPendingWinnings {
+                    player: Addr::unchecked(player),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            pending.winnings,
+            vec![PendingWinning {
+                room_id: "3".to_string(),
+                amount: Uint128::new(amount),
+            }]
+        );
+    }
+}
+
+#[test]
+fn test_multiple_bets_per_round() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let bet = |router: &mut App, direction, amount, replace| {
+        router.execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction,
+                    amount: Uint128::new(amount),
+                }],
+                replace,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        )
+    };
+
+    bet(&mut router, Direction::Odd, 100, None).unwrap();
+    //the points and limits are checked on the combined bets of the round
+    let err = bet(&mut router, Direction::Even, 100, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::ExceedBetPoints {}
+    );
+    bet(&mut router, Direction::Single { id: 5 }, 50, None).unwrap();
+
+    let players: BetsInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPlayersForOneRoundOneRoom {
+                room_id: 1,
+                round_id: 0,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(players.bets_info.len(), 1);
+    assert_eq!(
+        players.bets_info[0].bet_info,
+        vec![
+            BetConfig {
+                direction: Direction::Odd,
+                amount: Uint128::new(100),
+            },
+            BetConfig {
+                direction: Direction::Single { id: 5 },
+                amount: Uint128::new(50),
+            },
+        ]
+    );
+
+    //replacing the bets refunds the previous stake
+    bet(&mut router, Direction::Single { id: 7 }, 250, Some(true)).unwrap();
+    let user1_balance = router
+        .wrap()
+        .query_balance("user1", "usei".to_string())
+        .unwrap();
+    assert_eq!(user1_balance.amount, Uint128::new(9750));
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::new(10250));
+    assert_eq!(bankroll.bankroll.locked, Uint128::new(9000));
+
+    //no more bets in the last 10 seconds before the round can close
+    let state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 1 })
+        .unwrap();
+    assert_eq!(state.betting_close, Some(110));
+    assert_eq!(state.earliest_close, Some(120));
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(111),
+        chain_id: "chain-1".to_string(),
+    });
+    let err = bet(&mut router, Direction::Odd, 100, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoMoreBets {}
+    );
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    let err = bet(&mut router, Direction::Odd, 100, None).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundFinished {}
+    );
+}
+
+#[test]
+fn test_cancel_and_modify_bets() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let execute = |router: &mut App, msg: &ExecuteMsg, amount: u128| {
+        let funds = if amount == 0 {
+            vec![]
+        } else {
+            vec![Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(amount),
+            }]
+        };
+        router.execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            msg,
+            &funds,
+        )
+    };
+    let check_funds = |router: &App, balance: u128, deposited: u128, locked: u128| {
+        let user1_balance = router
+            .wrap()
+            .query_balance("user1", "usei".to_string())
+            .unwrap();
+        assert_eq!(user1_balance.amount, Uint128::new(balance));
+        let bankroll: BankrollResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetBankroll { room_id: 1 },
+            )
+            .unwrap();
+        assert_eq!(bankroll.bankroll.deposited, Uint128::new(deposited));
+        assert_eq!(bankroll.bankroll.locked, Uint128::new(locked));
+    };
+
+    //nothing to change before the first bet
+    let err = execute(&mut router, &ExecuteMsg::CancelBet { room_id: 1 }, 0).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoBet {}
+    );
+
+    let bet = ExecuteMsg::Bet {
+        room_id: 1,
+        bet_info: vec![BetConfig {
+            direction: Direction::Single { id: 7 },
+            amount: Uint128::new(200),
+        }],
+        replace: None,
+    };
+    execute(&mut router, &bet, 200).unwrap();
+    check_funds(&router, 9800, 10200, 7200);
+
+    //lowering the bet refunds the difference and releases the exposure
+    let res = execute(
+        &mut router,
+        &ExecuteMsg::ModifyBet {
+            room_id: 1,
+            bet_info: vec![BetConfig {
+                direction: Direction::Single { id: 7 },
+                amount: Uint128::new(100),
+            }],
+        },
+        0,
+    )
+    .unwrap();
+    let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert!(wasm
+        .attributes
+        .iter()
+        .any(|a| a.key == "action" && a.value == "modify_bet"));
+    assert!(wasm
+        .attributes
+        .iter()
+        .any(|a| a.key == "refund" && a.value == "100"));
+    check_funds(&router, 9900, 10100, 3600);
+
+    //raising it only charges the difference
+    let modify = ExecuteMsg::ModifyBet {
+        room_id: 1,
+        bet_info: vec![
+            BetConfig {
+                direction: Direction::Single { id: 7 },
+                amount: Uint128::new(150),
+            },
+            BetConfig {
+                direction: Direction::Odd,
+                amount: Uint128::new(100),
+            },
+        ],
+    };
+    let err = execute(&mut router, &modify, 250).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InsufficientFunds {}
+    );
+    execute(&mut router, &modify, 150).unwrap();
+    check_funds(&router, 9750, 10250, 5600);
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(60),
+        chain_id: "chain-1".to_string(),
+    });
+    execute(&mut router, &ExecuteMsg::CancelBet { room_id: 1 }, 0).unwrap();
+    check_funds(&router, 10000, 10000, 0);
+    let players: BetsInfoResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPlayersForOneRoundOneRoom {
+                room_id: 1,
+                round_id: 0,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(players.bets_info.is_empty());
+
+    //the bets are locked in the last 30 seconds of the betting time, which closes at 110
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(81),
+        chain_id: "chain-1".to_string(),
+    });
+    execute(&mut router, &bet, 200).unwrap();
+    let err = execute(&mut router, &ExecuteMsg::CancelBet { room_id: 1 }, 0).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BetsLocked {}
+    );
+    let err = execute(&mut router, &modify, 50).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BetsLocked {}
+    );
+
+    //a lock shorter than the no more bets window still locks the last seconds of the betting
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: RoomConfig {
+                    room_name: "SHORT LOCK".to_string(),
+                    game_denom: AssetInfo::NativeToken {
+                        denom: "usei".to_string(),
+                    },
+                    nft_id: "SEI".to_string(),
+                    max_bet: Uint128::new(100000),
+                    min_bet: Uint128::new(100),
+                    next_round_seconds: 120,
+                    bet_lock_seconds: 5,
+                    no_more_bets_seconds: 10,
+                    keeper_reward: None,
+                    risk_fraction: None,
+                    wheel: Wheel::American,
+                    payout_table: PayoutTable::standard(&Wheel::American),
+                    zero_rule: ZeroRule::Standard,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(5000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(5000),
+            }],
+        )
+        .unwrap();
+    commit_round(&mut router, &roulette_address, 3, b"round_0_secret");
+    let short_lock_bet = ExecuteMsg::Bet {
+        room_id: 3,
+        bet_info: vec![BetConfig {
+            direction: Direction::Odd,
+            amount: Uint128::new(100),
+        }],
+        replace: None,
+    };
+    //the round starts at 81, so its betting closes at 191
+    execute(&mut router, &short_lock_bet, 100).unwrap();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(186),
+        chain_id: "chain-1".to_string(),
+    });
+    execute(&mut router, &ExecuteMsg::CancelBet { room_id: 3 }, 0).unwrap();
+    execute(&mut router, &short_lock_bet, 100).unwrap();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(187),
+        chain_id: "chain-1".to_string(),
+    });
+    let err = execute(&mut router, &ExecuteMsg::CancelBet { room_id: 3 }, 0).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BetsLocked {}
+    );
+}
+
+#[test]
+fn test_refund_round_without_reveal() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(181),
+        chain_id: "chain-1".to_string(),
+    });
+
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: None,
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    //the stake is credited back and waits to be claimed
+    settle_round(&mut router, &roulette_address, 1, 0);
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "1".to_string(),
+            amount: Uint128::new(100),
+        }]
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Claim { room_id: 1 },
+            &[],
+        )
+        .unwrap();
+
+    let user1_balance = router
+        .wrap()
+        .query_balance("user1", "usei".to_string())
+        .unwrap();
+    assert_eq!(user1_balance.amount, Uint128::new(10000));
+
+    let winner: WinnerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetWinnerRound {
+                room_id: 1,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(winner.winner.winner, None);
+}
+
+#[test]
+fn test_refund_round_after_timeout() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    let refund = |router: &mut App, player: &str| {
+        router.execute_contract(
+            Addr::unchecked(player),
+            roulette_address.clone(),
+            &ExecuteMsg::RefundRound {
+                room_id: 1,
+                round_id: 0,
+            },
+            &[],
+        )
+    };
+
+    //the round can still be closed until the refund timeout is over
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(300),
+        chain_id: "chain-1".to_string(),
+    });
+    let err = refund(&mut router, "user1").unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RefundPending {}
+    );
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(720),
+        chain_id: "chain-1".to_string(),
+    });
+    //only the players of the round can refund it
+    let err = refund(&mut router, "user2").unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoBet {}
+    );
+    refund(&mut router, "user1").unwrap();
+
+    let winner: WinnerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetWinnerRound {
+                room_id: 1,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(winner.winner.winner, None);
+    assert!(winner.winner.void);
+    let winner_list: WinnerListResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetWinnerLists {
+                room_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(winner_list.winner_list, vec![winner.winner]);
+
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "1".to_string(),
+            amount: Uint128::new(100),
+        }]
+    );
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.locked, Uint128::zero());
+
+    let err = refund(&mut router, "user1").unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundClosed { round_id: 0 }
+    );
+}
+
+#[test]
+fn test_claim_winnings() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    commit_round(&mut router, &roulette_address, 2, b"room_2_round_0_secret");
+
+    //user1 bets on the pocket each room is going to draw
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single {
+                        id: winning_pocket(1, 0, b"round_0_secret"),
+                    },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&ReceiveMsg::Bet {
+                    room_id: 2,
+                    bet_info: vec![BetConfig {
+                        direction: Direction::Single {
+                            id: winning_pocket(2, 0, b"room_2_round_0_secret"),
+                        },
+                        amount: Uint128::new(100),
+                    }],
+                    replace: None,
+                })
+                .unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+
+    for (room_id, secret) in [
+        (1, b"round_0_secret".to_vec()),
+        (2, b"room_2_round_0_secret".to_vec()),
+    ] {
+        router
+            .execute_contract(
+                Addr::unchecked("distributor"),
+                roulette_address.clone(),
+                &ExecuteMsg::CloseRound {
+                    room_id,
+                    secret: Some(Binary::from(secret)),
+                    beacon: None,
+                },
+                &[],
+            )
+            .unwrap();
+        settle_round(&mut router, &roulette_address, room_id, 0);
+    }
+
+    //nothing is paid on settlement, 36 times the bet minus the 40% platform fee is credited
+    let user1_balance = router
+        .wrap()
+        .query_balance("user1", "usei".to_string())
+        .unwrap();
+    assert_eq!(user1_balance.amount, Uint128::new(9900));
+
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![
+            PendingWinning {
+                room_id: "1".to_string(),
+                amount: Uint128::new(2160),
+            },
+            PendingWinning {
+                room_id: "2".to_string(),
+                amount: Uint128::new(2160),
+            },
+        ]
+    );
+
+    //the platform fee is paid out, the credited winnings can not be withdrawn from the pool
+    let withdraw_response: WithdrawResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetMaximumWithdrawlFromRoom { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(withdraw_response.amount, Uint128::new(10100 - 1440 - 2160));
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Claim { room_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let user1_balance = router
+        .wrap()
+        .query_balance("user1", "usei".to_string())
+        .unwrap();
+    assert_eq!(user1_balance.amount, Uint128::new(9900 + 2160));
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Claim { room_id: 1 },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToClaim {}
+    );
+
+    //claim the cw20 winnings of room 2
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::ClaimAll {},
+            &[],
+        )
+        .unwrap();
+    let token_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            token_address,
+            &Cw20QueryMsg::Balance {
+                address: "user1".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(token_balance.balance, Uint128::new(9900 + 2160));
+
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("user1"),
+            },
+        )
+        .unwrap();
+    assert!(pending.winnings.is_empty());
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address,
+            &ExecuteMsg::ClaimAll {},
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NothingToClaim {}
+    );
+}
+
+#[test]
+fn test_settle_round_in_batches() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+    //enough liquidity to pay three winners of a single number
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 1,
+                amount: Uint128::new(5000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(5000),
+            }],
+        )
+        .unwrap();
+
+    //everybody bets on the winning pocket
+    let winning_bet = ExecuteMsg::Bet {
+        room_id: 1,
+        bet_info: vec![BetConfig {
+            direction: Direction::Single {
+                id: winning_pocket(1, 0, b"round_0_secret"),
+            },
+            amount: Uint128::new(100),
+        }],
+        replace: None,
+    };
+    for player in ["user1", "user2", "sei_admin"] {
+        router
+            .execute_contract(
+                Addr::unchecked(player),
+                roulette_address.clone(),
+                &winning_bet,
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(100),
+                }],
+            )
+            .unwrap();
+    }
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(b"round_0_secret".to_vec())),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            roulette_address.clone(),
+            &ExecuteMsg::SettleRound {
+                room_id: 1,
+                round_id: 1,
+                limit: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::NoPendingSettlement { round_id: 1 }
+    );
+
+    //the first batch credits two of the three players
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            roulette_address.clone(),
+            &ExecuteMsg::SettleRound {
+                room_id: 1,
+                round_id: 0,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 1 })
+        .unwrap();
+    let settlement = state.settlement.unwrap();
+    assert_eq!(settlement.round_id, 0);
+    assert_eq!(settlement.total_bet_amount, Uint128::new(200));
+
+    //the next round can not take bets until the last one is settled
+    commit_round(&mut router, &roulette_address, 1, b"round_1_secret");
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundSettling {}
+    );
+
+    //the shares can not move while the bankroll still holds the stakes of the round
+    let lp_msgs = [
+        (
+            ExecuteMsg::Deposit {
+                room_id: 1,
+                amount: Uint128::new(1000),
+            },
+            vec![Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        ),
+        (
+            ExecuteMsg::WithdrawFromPool {
+                room_id: 1,
+                amount: Uint128::new(1000),
+            },
+            vec![],
+        ),
+        (
+            ExecuteMsg::QueueWithdrawal {
+                room_id: 1,
+                shares: Uint128::new(1000),
+            },
+            vec![],
+        ),
+    ];
+    for (msg, funds) in lp_msgs {
+        let err = router
+            .execute_contract(
+                Addr::unchecked("sei_admin"),
+                roulette_address.clone(),
+                &msg,
+                &funds,
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::RoundSettling {}
+        );
+    }
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            roulette_address.clone(),
+            &ExecuteMsg::SettleRound {
+                room_id: 1,
+                round_id: 0,
+                limit: Some(2),
+            },
+            &[],
+        )
+        .unwrap();
+
+    for player in ["user1", "user2", "sei_admin"] {
+        let pending: PendingWinningsResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetPendingWinnings {
+                    player: Addr::unchecked(player),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending.winnings[0].amount, Uint128::new(2160));
+    }
+
+    //the platform fee of all three winners is owed once the round is settled
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        bankroll.bankroll,
+        Bankroll {
+            deposited: Uint128::new(15300 - 3 * 2160 - 3 * 1440),
+            locked: Uint128::zero(),
+            fees_owed: Uint128::new(3 * 1440),
+            total_shares: Uint128::new(15000),
+            share_epoch: 0,
+        }
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("distributor"),
+            roulette_address.clone(),
+            &ExecuteMsg::CollectFees { room_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let distributor_balance = router
+        .wrap()
+        .query_balance("distributor", "usei".to_string())
+        .unwrap();
+    assert_eq!(distributor_balance.amount, Uint128::new(3 * 1440));
+
+    let state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 1 })
+        .unwrap();
+    assert_eq!(state.settlement, None);
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address,
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_close_round_with_drand_beacon() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address.clone());
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    //a zero period would divide by zero when the first bet binds a drand round
+    let err = router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    admin: Addr::unchecked("admin"),
+                    nft_contract: nft_address.clone(),
+                    distributor: Addr::unchecked("distributor"),
+                    platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+                    reveal_timeout_seconds: 60,
+                    refund_timeout_seconds: 600,
+                    min_house_edge: Decimal::zero(),
+                    randomness: RandomnessSource::Drand {
+                        pubkey: HexBinary::from_hex("adf63187b71c4c0db9833ea07a84bec8597c5148617143e72b1a8a5f0f64d5b0119e018df861ed8df7af185c17e60103").unwrap().into(),
+                        genesis_time: 0,
+                        period: 0,
+                    },
+                },
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidDrandConfig {}
+    );
+
+    //beacons of a test drand group with a 30 seconds period, see drand.rs
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    admin: Addr::unchecked("admin"),
+                    nft_contract: nft_address,
+                    distributor: Addr::unchecked("distributor"),
+                    platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+                    reveal_timeout_seconds: 60,
+                    refund_timeout_seconds: 600,
+                    min_house_edge: Decimal::zero(),
+                    randomness: RandomnessSource::Drand {
+                        pubkey: HexBinary::from_hex("adf63187b71c4c0db9833ea07a84bec8597c5148617143e72b1a8a5f0f64d5b0119e018df861ed8df7af185c17e60103").unwrap().into(),
+                        genesis_time: 0,
+                        period: 30,
+                    },
+                },
+            },
+            &[],
+        )
+        .unwrap();
+
+    //no commitment is needed with drand
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Even,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    //betting closes at 120, so the round is bound to drand round 6 published at 150
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(181),
+        chain_id: "chain-1".to_string(),
+    });
+
+    //the beacon is public after the reveal timeout, so the round can not be voided anymore
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: None,
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BeaconPublished { drand_round: 6 }
+    );
+
+    //a losing player can not get their stake back either once the winner is known
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(721),
+        chain_id: "chain-1".to_string(),
+    });
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::RefundRound {
+                room_id: 1,
+                round_id: 0,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::BeaconPublished { drand_round: 6 }
+    );
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: None,
+                beacon: Some(DrandBeacon {
+                    round: 7,
+                    signature: HexBinary::from_hex("8745ef7aa940b0e5542c22192d1c1116538b2d7a1f52c17e26e2031ec3bde74316d0085165c39be8c4de4ccea93b36ef096ddb6444beea5973a1a3294946920cbbc319c7a7fd64549237b4823b58cd3d5ac071b16e19affa7fc7633de80d8650").unwrap().into(),
+                }),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::WrongDrandRound {
+            expected: 6,
+            provided: 7
+        }
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: None,
+                beacon: Some(DrandBeacon {
+                    round: 6,
+                    signature: HexBinary::from_hex("8cd9e58df277c6435a70dab3813519a06d160a9b1c08cee31ba42b6927f6d33ea91da496e7147347d8b5ec25358eeab70a616a1fd2cbe064e843789f491bbf1ba05d587d0d77f39a0472f23875a00fb9b5c93405659b09b06f415b249c3fc43d").unwrap().into(),
+                }),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let winner: WinnerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetWinnerRound {
+                room_id: 1,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert!(winner.winner.winner.unwrap() < 38);
+}
+
+#[test]
+fn test_close_round_with_randomness_proxy() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address.clone());
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+    let proxy_address = init_randomness_proxy_contract(&mut router);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    admin: Addr::unchecked("admin"),
+                    nft_contract: nft_address,
+                    distributor: Addr::unchecked("distributor"),
+                    platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+                    reveal_timeout_seconds: 60,
+                    refund_timeout_seconds: 600,
+                    min_house_edge: Decimal::zero(),
+                    randomness: RandomnessSource::Proxy {
+                        contract: proxy_address.clone(),
+                    },
+                },
+            },
+            &[],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let close_round_msg = ExecuteMsg::CloseRound {
+        room_id: 1,
+        secret: None,
+        beacon: None,
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &close_round_msg,
+            &[],
+        )
+        .unwrap();
+
+    //the round is drawing until the proxy calls back
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &close_round_msg,
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RoundDrawing {}
+    );
+
+    //only the proxy can deliver randomness
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::ReceiveRandomness {
+                callback: RandomnessCallback {
+                    job_id: "1/0".to_string(),
+                    published: Timestamp::from_seconds(121),
+                    randomness: HexBinary::from(sha_256(b"fake").to_vec()),
+                },
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            proxy_address,
+            &ProxyExecuteMsg::Deliver {
+                job_id: "1/0".to_string(),
+                randomness: HexBinary::from(sha_256(b"proxy randomness").to_vec()),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let winner: WinnerResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetWinnerRound {
+                room_id: 1,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert!(winner.winner.winner.unwrap() < 38);
+}
+
+#[test]
+fn test_keeper_reward() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address.clone());
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+    let proxy_address = init_randomness_proxy_contract(&mut router);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    admin: Addr::unchecked("admin"),
+                    nft_contract: nft_address.clone(),
+                    distributor: Addr::unchecked("distributor"),
+                    platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+                    reveal_timeout_seconds: 60,
+                    refund_timeout_seconds: 600,
+                    min_house_edge: Decimal::zero(),
+                    randomness: RandomnessSource::Proxy {
+                        contract: proxy_address.clone(),
+                    },
+                },
+            },
+            &[],
+        )
+        .unwrap();
+
+    let room_config = |keeper_reward| RoomConfig {
+        room_name: "KEEPER".to_string(),
+        game_denom: AssetInfo::NativeToken {
+            denom: "usei".to_string(),
+        },
+        nft_id: "SEI".to_string(),
+        max_bet: Uint128::new(100000),
+        min_bet: Uint128::new(100),
+        next_round_seconds: 120,
+        bet_lock_seconds: 0,
+        no_more_bets_seconds: 0,
+        keeper_reward,
+        risk_fraction: None,
+        wheel: Wheel::American,
+        payout_table: PayoutTable::standard(&Wheel::American),
+        zero_rule: ZeroRule::Standard,
+    };
+    let err = router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: room_config(Some(Decimal::percent(150))),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidKeeperReward {}
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: room_config(Some(Decimal::percent(50))),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(10000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(10000),
+            }],
+        )
+        .unwrap();
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 3,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    let closeable_rounds = |router: &App| {
+        let res: CloseableRoundsResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::CloseableRounds {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        res.rounds
+    };
+    assert!(closeable_rounds(&router).is_empty());
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    assert_eq!(
+        closeable_rounds(&router),
+        vec![CloseableRound {
+            room_id: "3".to_string(),
+            round_id: "0".to_string(),
+            closeable_since: 120,
+            drand_round: None,
+            void: false,
+            keeper_reward: Some(Decimal::percent(50)),
+        }]
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("keeper"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 3,
+                secret: None,
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    //the round is drawing, so only the proxy can close it now
+    assert!(closeable_rounds(&router).is_empty());
+
+    //the odd bet loses, so the fee is 40% of the lost stake and the keeper gets half of it
+    let randomness = find_secret(3, 0, 38, |pocket| pocket == 2);
+    router
+        .execute_contract(
+            Addr::unchecked("anyone"),
+            proxy_address,
+            &ProxyExecuteMsg::Deliver {
+                job_id: "3/0".to_string(),
+                randomness: HexBinary::from(randomness),
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 3, 0);
+
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("keeper"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "3".to_string(),
+            amount: Uint128::new(20),
+        }]
+    );
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 3 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::new(10060));
+    assert_eq!(bankroll.bankroll.fees_owed, Uint128::new(20));
+
+    //with drand the keeper has to wait for the beacon bound to the round
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateConfig {
+                config: Config {
+                    admin: Addr::unchecked("admin"),
+                    nft_contract: nft_address,
+                    distributor: Addr::unchecked("distributor"),
+                    platform_fee: Decimal::from_ratio(40_u128, 100_u128),
+                    reveal_timeout_seconds: 60,
+                    refund_timeout_seconds: 600,
+                    min_house_edge: Decimal::zero(),
+                    randomness: RandomnessSource::Drand {
+                        pubkey: HexBinary::from_hex("adf63187b71c4c0db9833ea07a84bec8597c5148617143e72b1a8a5f0f64d5b0119e018df861ed8df7af185c17e60103").unwrap().into(),
+                        genesis_time: 0,
+                        period: 30,
+                    },
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 3,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+    //the round closes at 241, so it is bound to drand round 10 published at 270
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(250),
+        chain_id: "chain-1".to_string(),
+    });
+    assert!(closeable_rounds(&router).is_empty());
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(270),
+        chain_id: "chain-1".to_string(),
+    });
+    assert_eq!(
+        closeable_rounds(&router),
+        vec![CloseableRound {
+            room_id: "3".to_string(),
+            round_id: "1".to_string(),
+            closeable_since: 270,
+            drand_round: Some(10),
+            void: false,
+            keeper_reward: Some(Decimal::percent(50)),
+        }]
+    );
+}
+
+#[test]
+fn test_max_allowed_bet() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let update_bet_limit = |router: &mut App, risk_fraction| {
+        router.execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateBetLimit {
+                room_id: 1,
+                max_bet: Uint128::new(100000),
+                min_bet: Uint128::new(100),
+                risk_fraction,
+            },
+            &[],
+        )
+    };
+    let err = update_bet_limit(&mut router, Some(Decimal::zero())).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InvalidRiskFraction {}
+    );
+    //the room risks at most 10% of its free bankroll on one pocket
+    update_bet_limit(&mut router, Some(Decimal::percent(10))).unwrap();
+
+    let max_allowed_bet = |router: &App, direction: Direction| {
+        let res: MaxAllowedBetResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::MaxAllowedBet {
+                    room_id: 1,
+                    direction,
+                },
+            )
+            .unwrap();
+        res.max_bet
+    };
+    assert_eq!(
+        max_allowed_bet(&router, Direction::Single { id: 7 }),
+        Uint128::new(27)
+    );
+    assert_eq!(
+        max_allowed_bet(&router, Direction::Column { id: 1 }),
+        Uint128::new(333)
+    );
+    assert_eq!(max_allowed_bet(&router, Direction::Odd), Uint128::new(500));
+
+    let bet = |router: &mut App, amount: u128| {
+        router.execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(amount),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        )
+    };
+    let err = bet(&mut router, 600).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RiskLimitExceeded {
+            pocket: 1,
+            payout: Uint128::new(1200),
+            max_payout: Uint128::new(1000),
+        }
+    );
+    bet(&mut router, 500).unwrap();
+
+    //the locked payout of the bet leaves less free bankroll for the next ones
+    assert_eq!(max_allowed_bet(&router, Direction::Odd), Uint128::new(475));
+}
+
+#[test]
+fn test_max_allowed_bet_with_fractional_payout() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    //the even money bets pay 1.95 times the stake
+    let mut payout_table = PayoutTable::standard(&Wheel::American);
+    payout_table.even_money = Decimal::from_ratio(195_u128, 100_u128);
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdatePayoutTable {
+                room_id: 1,
+                payout_table,
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::UpdateBetLimit {
+                room_id: 1,
+                max_bet: Uint128::new(100000),
+                min_bet: Uint128::new(100),
+                risk_fraction: Some(Decimal::percent(20)),
+            },
+            &[],
+        )
+        .unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    //2000 of the free bankroll pays at most 2000 / 1.95 of stake
+    let res: MaxAllowedBetResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::MaxAllowedBet {
+                room_id: 1,
+                direction: Direction::Odd,
+            },
+        )
+        .unwrap();
+    assert_eq!(res.max_bet, Uint128::new(1025));
+
+    let bet = |router: &mut App, amount: u128| {
+        router.execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Odd,
+                    amount: Uint128::new(amount),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(amount),
+            }],
+        )
+    };
+    let err = bet(&mut router, 1100).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::RiskLimitExceeded {
+            pocket: 1,
+            payout: Uint128::new(2145),
+            max_payout: Uint128::new(2000),
+        }
+    );
+    bet(&mut router, 1025).unwrap();
+}
+
+#[test]
+fn test_round_exposure() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let bet = |router: &mut App, player: &str, direction: Direction, amount: u128| {
+        router
+            .execute_contract(
+                Addr::unchecked(player),
+                roulette_address.clone(),
+                &ExecuteMsg::Bet {
+                    room_id: 1,
+                    bet_info: vec![BetConfig {
+                        direction,
+                        amount: Uint128::new(amount),
+                    }],
+                    replace: None,
+                },
+                &[Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(amount),
+                }],
+            )
+            .unwrap();
+    };
+    let round_exposure = |router: &App| {
+        let res: RoundExposureResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetRoundExposure {
+                    room_id: 1,
+                    round_id: 0,
+                },
+            )
+            .unwrap();
+        res
+    };
+
+    let res = round_exposure(&router);
+    assert_eq!(res.exposure.pockets, vec![Uint128::zero(); 38]);
+    assert_eq!(res.reserve, Uint128::zero());
+
+    bet(&mut router, "user1", Direction::Single { id: 7 }, 100);
+    bet(&mut router, "user2", Direction::Odd, 500);
+    let res = round_exposure(&router);
+    assert_eq!(res.exposure.pockets[7], Uint128::new(3600 + 1000));
+    assert_eq!(res.exposure.pockets[1], Uint128::new(1000));
+    assert_eq!(res.exposure.pockets[2], Uint128::zero());
+    assert_eq!(res.exposure.total_stake, Uint128::new(600));
+    assert_eq!(res.reserve, Uint128::new(4600));
+
+    //the bankroll locks the reserve of the round
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.locked, Uint128::new(4600));
+
+    //a cancelled bet leaves the exposure
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::CancelBet { room_id: 1 },
+            &[],
+        )
+        .unwrap();
+    let res = round_exposure(&router);
+    assert_eq!(res.exposure.pockets[7], Uint128::new(1000));
+    assert_eq!(res.exposure.total_stake, Uint128::new(500));
+    assert_eq!(res.reserve, Uint128::new(1000));
+}
+
+#[test]
+fn test_simulate_bet() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let simulate_bet = |router: &App, bet_info: Vec<BetConfig>| {
+        let res: SimulateBetResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::SimulateBet {
+                    room_id: 1,
+                    player: Addr::unchecked("user1"),
+                    bet_info,
+                },
+            )
+            .unwrap();
+        res
+    };
+    let single = |id: u32, amount: u128| BetConfig {
+        direction: Direction::Single { id },
+        amount: Uint128::new(amount),
+    };
+
+    //the payout of the winning pocket is shown without the 40% platform fee
+    let res = simulate_bet(&router, vec![single(7, 100)]);
+    assert!(res.success);
+    assert_eq!(res.error, None);
+    assert_eq!(res.total_stake, Uint128::new(100));
+    assert_eq!(
+        res.payouts,
+        vec![PocketPayout {
+            pocket: 7,
+            payout: Uint128::new(2160),
+        }]
+    );
+
+    //the simulation does not place the bet
+    let exposure: RoundExposureResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetRoundExposure {
+                room_id: 1,
+                round_id: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(exposure.reserve, Uint128::zero());
+
+    let res = simulate_bet(&router, vec![single(7, 50)]);
+    assert!(!res.success);
+    assert_eq!(
+        res.error,
+        Some(
+            ContractError::Std(StdError::generic_err(
+                "You must bet with the amount between 100  and 100000"
+            ))
+            .to_string()
+        )
+    );
+
+    let res = simulate_bet(&router, (0..20).map(|id| single(id, 100)).collect());
+    assert_eq!(
+        res.error,
+        Some(ContractError::ExceedBetPoints {}.to_string())
+    );
+    assert_eq!(res.payouts.len(), 20);
+
+    //the bet on the table and the new chips together can not exceed the room
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![single(7, 200)],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(200),
+            }],
+        )
+        .unwrap();
+    let res = simulate_bet(&router, vec![single(7, 100)]);
+    assert_eq!(
+        res.error,
+        Some(
+            ContractError::Std(StdError::generic_err(
+                "The room will have 10300 of tokens after this bet, but if 7 is selected as winner, the maximum reward will be 10800"
+            ))
+            .to_string()
+        )
+    );
+
+    let state: AllStateResponse = router
+        .wrap()
+        .query_wasm_smart(roulette_address.clone(), &QueryMsg::AllState { room_id: 1 })
+        .unwrap();
+    router.update_block(|block| {
+        block.time = Timestamp::from_seconds(state.betting_close.unwrap() + 1);
+    });
+    let res = simulate_bet(&router, vec![single(8, 100)]);
+    assert_eq!(res.error, Some(ContractError::NoMoreBets {}.to_string()));
+}
+
+#[test]
+fn test_withdraw() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    commit_round(&mut router, &roulette_address, 1, b"round_0_secret");
+
+    let bet_msg = ExecuteMsg::Bet {
+        room_id: 1,
+        bet_info: vec![BetConfig {
+            direction: Direction::SecondOfThird,
+            amount: Uint128::new(100),
+        }],
+        replace: None,
+    };
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &bet_msg,
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    //money sent to the contract outside of a deposit does not fund the room
+    router
+        .send_tokens(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        )
+        .unwrap();
+
+    //the deposit and the stake minus the 3x payout locked for the bet
+    let maximum_withdrawal_sei: WithdrawResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetMaximumWithdrawlFromRoom { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(maximum_withdrawal_sei.amount, Uint128::new(10100 - 300));
+
+    let err = router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 1,
+                amount: Uint128::new(9801),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::WithdrawalMoneyExceeded {
+            withdrawal_amount: Uint128::new(9800),
+            amount: Uint128::new(9801),
+        }
+    );
+
+    let withdraw_msg = ExecuteMsg::WithdrawFromPool {
+        room_id: 1,
+        amount: Uint128::new(9000),
+    };
+
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap();
+
+    let winner_list: WinnerListResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetWinnerLists {
+                room_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+    println!("winner_list, {:?}", winner_list)
+}
+
+#[test]
+fn test_deposit() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    let deposit_msg = ExecuteMsg::Deposit {
+        room_id: 1,
+        amount: Uint128::new(100),
+    };
+    router
+        .execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &deposit_msg,
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+
+    let native_token_balance = router
+        .wrap()
+        .query_balance(roulette_address.clone(), "usei".to_string())
+        .unwrap();
+
+    println!("native_token_balance {:?}", native_token_balance);
+
+    //the deposited amount is the amount actually sent by the token
+    router
+        .execute_contract(
+            Addr::unchecked("test_admin"),
+            token_address.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: roulette_address.to_string(),
+                amount: Uint128::new(200),
+                msg: to_binary(&ReceiveMsg::Deposit { room_id: 2 }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 2 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::new(5200));
+
+    let token_balance: BalanceResponse = router
+        .wrap()
+        .query_wasm_smart(
+            token_address,
+            &Cw20QueryMsg::Balance {
+                address: roulette_address.to_string(),
+            },
+        )
+        .unwrap();
+
+    println!("token balance {:?}", token_balance)
+}
+
+#[test]
+fn test_liquidity_providers() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    let secret = find_secret(1, 0, 38, |pocket| pocket != 7);
+    commit_round(&mut router, &roulette_address, 1, &secret);
+
+    //anyone can provide liquidity next to the room owner
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 1,
+                amount: Uint128::new(5000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(5000),
+            }],
+        )
+        .unwrap();
+    let liquidity = |router: &App, provider: &str| {
+        let res: LiquidityResponse = router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetLiquidity {
+                    room_id: 1,
+                    provider: Addr::unchecked(provider),
+                },
+            )
+            .unwrap();
+        res
+    };
+    assert_eq!(
+        liquidity(&router, "user2"),
+        LiquidityResponse {
+            room_id: "1".to_string(),
+            shares: Uint128::new(5000),
+            total_shares: Uint128::new(15000),
+            value: Uint128::new(5000),
+            withdrawable: Uint128::new(5000),
+        }
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap();
+    //the reserve locked for the round is shared by the providers
+    assert_eq!(
+        liquidity(&router, "user2").withdrawable,
+        Uint128::new((15100 - 3600) / 3)
+    );
+
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    //the winner may be public once the betting is closed, so the shares can not move
+    let err = router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 1,
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::PoolLocked {}
+    );
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(secret)),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 1, 0);
+
+    //the lost stake minus the platform fee is shared pro rata
+    assert_eq!(liquidity(&router, "sei_admin").value, Uint128::new(10040));
+    assert_eq!(liquidity(&router, "user2").withdrawable, Uint128::new(5020));
+
+    let withdraw = |router: &mut App, amount: u128| {
+        router.execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 1,
+                amount: Uint128::new(amount),
+            },
+            &[],
+        )
+    };
+    let err = withdraw(&mut router, 5021).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::WithdrawalMoneyExceeded {
+            withdrawal_amount: Uint128::new(5020),
+            amount: Uint128::new(5021),
+        }
+    );
+    withdraw(&mut router, 5020).unwrap();
+    assert_eq!(liquidity(&router, "user2").shares, Uint128::zero());
+    let user2_balance = router
+        .wrap()
+        .query_balance("user2", "usei".to_string())
+        .unwrap();
+    assert_eq!(user2_balance.amount, Uint128::new(10020));
+}
+
+#[test]
+fn test_deposit_into_depleted_bankroll() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    let secret = find_secret(1, 0, 38, |pocket| pocket == 7);
+    commit_round(&mut router, &roulette_address, 1, &secret);
+
+    //a winning single number takes the whole bankroll left in the room
     router
         .execute_contract(
             Addr::unchecked("sei_admin"),
             roulette_address.clone(),
-            &deposit_msg,
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 1,
+                amount: Uint128::new(6500),
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
             &[Coin {
                 denom: "usei".to_string(),
                 amount: Uint128::new(100),
             }],
         )
         .unwrap();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(secret)),
+                beacon: None,
+            },
+            &[],
+        )
+        .unwrap();
+    settle_round(&mut router, &roulette_address, 1, 0);
 
-    let native_token_balance = router
+    let bankroll: BankrollResponse = router
         .wrap()
-        .query_balance(roulette_address.clone(), "usei".to_string())
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
         .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::zero());
+    assert_eq!(bankroll.bankroll.total_shares, Uint128::new(3500));
 
-    println!("native_token_balance {:?}", native_token_balance);
+    //the worthless shares of sei_admin take no part of a new deposit
+    router
+        .execute_contract(
+            Addr::unchecked("user2"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 1,
+                amount: Uint128::new(1000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        )
+        .unwrap();
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::new(1000));
+    assert_eq!(bankroll.bankroll.total_shares, Uint128::new(1000));
+    assert_eq!(bankroll.bankroll.share_epoch, 1);
 
-    let deposit_msg = ExecuteMsg::Deposit {
-        room_id: 2,
-        amount: Uint128::new(200),
+    let liquidity = |router: &App, provider: &str| -> LiquidityResponse {
+        router
+            .wrap()
+            .query_wasm_smart(
+                roulette_address.clone(),
+                &QueryMsg::GetLiquidity {
+                    room_id: 1,
+                    provider: Addr::unchecked(provider),
+                },
+            )
+            .unwrap()
     };
+    assert_eq!(liquidity(&router, "sei_admin").shares, Uint128::zero());
+    assert_eq!(liquidity(&router, "user2").value, Uint128::new(1000));
+}
+
+#[test]
+fn test_withdrawal_queue() {
+    let mut router = mock_app();
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(0),
+        chain_id: "chain-1".to_string(),
+    });
+
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+    let secret = find_secret(1, 0, 38, |pocket| pocket != 7);
+    commit_round(&mut router, &roulette_address, 1, &secret);
 
+    //the single locks most of the bankroll during the round
     router
         .execute_contract(
-            Addr::unchecked("test_admin"),
-            token_address.clone(),
-            &Cw20ExecuteMsg::IncreaseAllowance {
-                spender: roulette_address.to_string(),
-                amount: Uint128::new(200),
-                expires: None,
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 1,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(250),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(250),
+            }],
+        )
+        .unwrap();
+
+    let queue_withdrawal = |router: &mut App, shares: u128| {
+        router.execute_contract(
+            Addr::unchecked("sei_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::QueueWithdrawal {
+                room_id: 1,
+                shares: Uint128::new(shares),
+            },
+            &[],
+        )
+    };
+    let err = queue_withdrawal(&mut router, 20000).unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::InsufficientShares {
+            shares: Uint128::new(10000),
+        }
+    );
+    queue_withdrawal(&mut router, 5000).unwrap();
+
+    let queue: WithdrawalQueueResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetWithdrawalQueue {
+                room_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        queue.withdrawals,
+        vec![QueuedWithdrawal {
+            id: 0,
+            provider: Addr::unchecked("sei_admin"),
+            shares: Uint128::new(5000),
+            requested_at: 0,
+            value: Uint128::new(5125),
+        }]
+    );
+
+    //a request not paid yet can be cancelled by its provider only
+    queue_withdrawal(&mut router, 2000).unwrap();
+    let cancel_withdrawal = |router: &mut App, provider: &str| {
+        router.execute_contract(
+            Addr::unchecked(provider),
+            roulette_address.clone(),
+            &ExecuteMsg::CancelWithdrawal {
+                room_id: 1,
+                withdrawal_id: 1,
             },
             &[],
         )
+    };
+    let err = cancel_withdrawal(&mut router, "user2").unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::Unauthorized {}
+    );
+    cancel_withdrawal(&mut router, "sei_admin").unwrap();
+    let liquidity: LiquidityResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetLiquidity {
+                room_id: 1,
+                provider: Addr::unchecked("sei_admin"),
+            },
+        )
         .unwrap();
+    assert_eq!(liquidity.shares, Uint128::new(5000));
+    let err = cancel_withdrawal(&mut router, "sei_admin").unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnknownWithdrawal { withdrawal_id: 1 }
+    );
 
+    router.set_block(BlockInfo {
+        height: 0,
+        time: Timestamp::from_seconds(121),
+        chain_id: "chain-1".to_string(),
+    });
     router
         .execute_contract(
-            Addr::unchecked("test_admin"),
+            Addr::unchecked("user1"),
             roulette_address.clone(),
-            &deposit_msg,
+            &ExecuteMsg::CloseRound {
+                room_id: 1,
+                secret: Some(Binary::from(secret)),
+                beacon: None,
+            },
             &[],
         )
         .unwrap();
+    settle_round(&mut router, &roulette_address, 1, 0);
 
-    let token_balance: BalanceResponse = router
+    //the settlement pays the request out of the released bankroll, the lost stake included
+    let queue: WithdrawalQueueResponse = router
         .wrap()
         .query_wasm_smart(
-            token_address,
-            &Cw20QueryMsg::Balance {
-                address: roulette_address.to_string(),
+            roulette_address.clone(),
+            &QueryMsg::GetWithdrawalQueue {
+                room_id: 1,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert!(queue.withdrawals.is_empty());
+    let pending: PendingWinningsResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetPendingWinnings {
+                player: Addr::unchecked("sei_admin"),
             },
         )
         .unwrap();
+    assert_eq!(
+        pending.winnings,
+        vec![PendingWinning {
+            room_id: "1".to_string(),
+            amount: Uint128::new(5075),
+        }]
+    );
+    let bankroll: BankrollResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address.clone(),
+            &QueryMsg::GetBankroll { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(bankroll.bankroll.deposited, Uint128::new(5075));
+    assert_eq!(bankroll.bankroll.total_shares, Uint128::new(5000));
+}
 
-    println!("token balance {:?}", token_balance)
+#[test]
+fn test_shared_denom_rooms() {
+    let mut router = mock_app();
+    let nft_address = init_cw721_contract_and_mint(&mut router);
+    let roulette_address = init_roulette_contract(&mut router, nft_address);
+    let token_address = init_cw20_contract(&mut router, &roulette_address);
+
+    init_two_rooms(&mut router, &roulette_address, &token_address).unwrap();
+    mint_gaming_tokens_for_users(&mut router, &roulette_address).unwrap();
+
+    //room 3 plays with usei like room 1, but belongs to the owner of the TEST nft
+    router
+        .execute_contract(
+            Addr::unchecked("admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::AddRoom {
+                room_info: RoomConfig {
+                    room_name: "SEI 2".to_string(),
+                    game_denom: AssetInfo::NativeToken {
+                        denom: "usei".to_string(),
+                    },
+                    nft_id: "TEST".to_string(),
+                    max_bet: Uint128::new(100000),
+                    min_bet: Uint128::new(100),
+                    next_round_seconds: 120,
+                    bet_lock_seconds: 0,
+                    no_more_bets_seconds: 0,
+                    keeper_reward: None,
+                    risk_fraction: None,
+                    wheel: Wheel::American,
+                    payout_table: PayoutTable::standard(&Wheel::American),
+                    zero_rule: ZeroRule::Standard,
+                },
+            },
+            &[],
+        )
+        .unwrap();
+    router
+        .sudo(SudoMsg::Bank(BankSudo::Mint {
+            to_address: "test_admin".to_string(),
+            amount: vec![
+                Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(1000),
+                },
+                Coin {
+                    denom: "uatom".to_string(),
+                    amount: Uint128::new(1000),
+                },
+            ],
+        }))
+        .unwrap();
+
+    //coins of another denom would not belong to any room
+    let err = router
+        .execute_contract(
+            Addr::unchecked("test_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(1000),
+            },
+            &[
+                Coin {
+                    denom: "uatom".to_string(),
+                    amount: Uint128::new(1000),
+                },
+                Coin {
+                    denom: "usei".to_string(),
+                    amount: Uint128::new(1000),
+                },
+            ],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::UnexpectedFunds {}
+    );
+
+    router
+        .execute_contract(
+            Addr::unchecked("test_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::Deposit {
+                room_id: 3,
+                amount: Uint128::new(1000),
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(1000),
+            }],
+        )
+        .unwrap();
+
+    //the bankroll of room 1 does not back the bets of room 3
+    commit_round(&mut router, &roulette_address, 3, b"round_0_secret");
+    router
+        .execute_contract(
+            Addr::unchecked("user1"),
+            roulette_address.clone(),
+            &ExecuteMsg::Bet {
+                room_id: 3,
+                bet_info: vec![BetConfig {
+                    direction: Direction::Single { id: 7 },
+                    amount: Uint128::new(100),
+                }],
+                replace: None,
+            },
+            &[Coin {
+                denom: "usei".to_string(),
+                amount: Uint128::new(100),
+            }],
+        )
+        .unwrap_err();
+
+    //and the owner of room 3 can only withdraw its own deposit
+    let err = router
+        .execute_contract(
+            Addr::unchecked("test_admin"),
+            roulette_address.clone(),
+            &ExecuteMsg::WithdrawFromPool {
+                room_id: 3,
+                amount: Uint128::new(2000),
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.downcast::<ContractError>().unwrap(),
+        ContractError::WithdrawalMoneyExceeded {
+            withdrawal_amount: Uint128::new(1000),
+            amount: Uint128::new(2000),
+        }
+    );
+
+    let maximum_withdrawal: WithdrawResponse = router
+        .wrap()
+        .query_wasm_smart(
+            roulette_address,
+            &QueryMsg::GetMaximumWithdrawlFromRoom { room_id: 1 },
+        )
+        .unwrap();
+    assert_eq!(maximum_withdrawal.amount, Uint128::new(10000));
 }
 
 #[test]